@@ -0,0 +1,142 @@
+use serde_json::{json, Value};
+
+/// Name of the search-time analyzer applying the synonyms and stopwords of an index.
+pub const SEARCH_ANALYZER: &str = "carrel_search";
+
+const SYNONYM_FILTER: &str = "carrel_synonyms";
+const STOPWORD_FILTER: &str = "carrel_stopwords";
+
+/// Fields of CarrelSearchResultItem searched with the search analyzer.
+pub const SEARCH_ANALYZED_FIELDS: [&str; 5] = ["title", "text", "context", "sourceName", "tags"];
+
+/// Synonyms and stopwords of an index, applied to the query text only by the search analyzer.
+/// Synonyms from a file on the nodes can be reloaded in place, inline rules need a rebuilt index.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchAnalysis {
+    /// Rules in the Solr format: "ai, artificial intelligence" or "ml => machine learning".
+    pub synonyms: Vec<String>,
+
+    /// Synonyms file relative to the config directory of every node, used instead of the inline rules.
+    pub synonyms_path: Option<String>,
+
+    pub stopwords: Vec<String>,
+}
+
+impl SearchAnalysis {
+    // terms that all match each other
+    pub fn with_synonyms(mut self, terms: &[&str]) -> Self {
+        self.synonyms.push(terms.join(", "));
+        self
+    }
+
+    pub fn with_synonym_rule(mut self, rule: &str) -> Self {
+        self.synonyms.push(rule.to_string());
+        self
+    }
+
+    // synonyms read from the file, reloaded with DistantClient::reload_search_analyzers after editing it
+    pub fn with_synonyms_path(mut self, path: &str) -> Self {
+        self.synonyms_path = Some(path.to_string());
+        self
+    }
+
+    pub fn with_stopwords(mut self, stopwords: &[&str]) -> Self {
+        self.stopwords.extend(stopwords.iter().map(|stopword| stopword.to_string()));
+        self
+    }
+
+    // index settings defining the search analyzer
+    pub fn to_settings(&self) -> Value {
+        // an updateable filter may only be used at search time, which is all SEARCH_ANALYZER is used for
+        let synonym_filter = match &self.synonyms_path {
+            Some(path) => json!({
+                "type": "synonym_graph",
+                "synonyms_path": path,
+                "updateable": true
+            }),
+            None => json!({
+                "type": "synonym_graph",
+                "synonyms": self.synonyms
+            }),
+        };
+        json!({
+            "analysis": {
+                "filter": {
+                    SYNONYM_FILTER: synonym_filter,
+                    STOPWORD_FILTER: {
+                        "type": "stop",
+                        "stopwords": self.stopwords
+                    }
+                },
+                "analyzer": {
+                    SEARCH_ANALYZER: {
+                        "type": "custom",
+                        "tokenizer": "standard",
+                        // synonyms before stopwords, a rule containing a stopword would fail to parse otherwise
+                        "filter": ["lowercase", SYNONYM_FILTER, STOPWORD_FILTER]
+                    }
+                }
+            }
+        })
+    }
+
+    // read back from the settings of an index as returned by the get settings API
+    pub fn from_settings(settings: &Value) -> Self {
+        let filters = &settings["index"]["analysis"]["filter"];
+        SearchAnalysis {
+            synonyms: strings(&filters[SYNONYM_FILTER]["synonyms"]),
+            synonyms_path: filters[SYNONYM_FILTER]["synonyms_path"].as_str().map(str::to_string),
+            stopwords: strings(&filters[STOPWORD_FILTER]["stopwords"]),
+        }
+    }
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value.as_array()
+        .map(|values| values.iter().filter_map(|value| value.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+/// Mapping properties pointing the search analyzed fields at the search analyzer.
+pub fn search_analyzer_properties() -> Value {
+    let properties: serde_json::Map<String, Value> = SEARCH_ANALYZED_FIELDS.iter()
+        .map(|field| (field.to_string(), json!({
+            "type": "text",
+            "analyzer": "standard",
+            "search_analyzer": SEARCH_ANALYZER,
+            "fields": {
+                "keyword": { "type": "keyword", "ignore_above": 256 }
+            }
+        })))
+        .collect();
+    Value::Object(properties)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use super::{SearchAnalysis, SEARCH_ANALYZER};
+
+    #[test]
+    fn test_settings_round_trip() {
+        let search_analysis = SearchAnalysis::default()
+            .with_synonyms(&["ai", "artificial intelligence"])
+            .with_stopwords(&["et", "al"]);
+        let settings = search_analysis.to_settings();
+        assert_eq!(settings["analysis"]["filter"]["carrel_synonyms"]["synonyms"], json!(["ai, artificial intelligence"]));
+        assert_eq!(settings["analysis"]["analyzer"][SEARCH_ANALYZER]["filter"][1], "carrel_synonyms");
+        // the get settings API nests the settings under "index"
+        assert_eq!(SearchAnalysis::from_settings(&json!({ "index": settings })), search_analysis);
+    }
+
+    #[test]
+    fn test_synonyms_path_is_updateable() {
+        let search_analysis = SearchAnalysis::default().with_synonyms_path("analysis/synonyms.txt");
+        let settings = search_analysis.to_settings();
+        let filter = &settings["analysis"]["filter"]["carrel_synonyms"];
+        assert_eq!(filter["synonyms_path"], "analysis/synonyms.txt");
+        assert_eq!(filter["updateable"], true);
+        assert!(filter.get("synonyms").is_none());
+        assert_eq!(SearchAnalysis::from_settings(&json!({ "index": settings })), search_analysis);
+    }
+}
@@ -1,23 +1,51 @@
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::fs::File;
+use std::future::Future;
 use std::io::{ErrorKind, Write};
-use carrel_commons::carrel::shared::search::v1::CarrelSearchResultItem;
+use carrel_commons::carrel::shared::search::v1::{CarrelSearchResponse, CarrelSearchResultItem};
 use carrel_commons::generic::api::query::v1::SearchQuery;
-use elasticsearch::{BulkParts, DeleteParts, Elasticsearch, Error, IndexParts, ScrollParts, SearchParts};
+use elasticsearch::{BulkParts, CountParts, DeleteByQueryParts, DeleteParts, Elasticsearch, Error, IndexParts, MsearchParts, ScrollParts, SearchParts, UpdateByQueryParts, UpdateParts};
+use elasticsearch::cat::CatAliasesParts;
+use elasticsearch::cluster::ClusterHealthParts;
 use elasticsearch::cat::{CatIndices, CatIndicesParts};
 use elasticsearch::http::request::JsonBody;
 use elasticsearch::http::response::Response;
 use elasticsearch::http::StatusCode;
 use elasticsearch::http::transport::BuildError;
-use elasticsearch::indices::IndicesDeleteParts;
-use elasticsearch::params::Level::Indices;
+use elasticsearch::indices::{IndicesAnalyzeParts, IndicesCreateParts, IndicesDeleteParts, IndicesExistsParts, IndicesGetAliasParts, IndicesGetMappingParts, IndicesGetSettingsParts, IndicesPutAliasParts, IndicesRefreshParts, IndicesReloadSearchAnalyzersParts, IndicesStatsParts};
+use elasticsearch::params::{Bytes, Conflicts, Level, WaitForStatus};
+use elasticsearch::tasks::{TasksCancelParts, TasksGetParts};
 use serde::Serialize;
-use serde_json::{json, Value};
-use log::info;
+use serde_json::{json, Map, Value};
+use log::{info, warn};
+use crate::analysis::SearchAnalysis;
 use crate::errors::DistantError;
+use crate::embeddings::{embedding_text, is_usable_embedding, EmbeddingProvider};
+use crate::language::{language_field, LanguageAnalysis, LANGUAGE_FIELDS};
+use crate::mappings::{add_language_fields, add_search_analysis, carrel_index_body, carrel_index_body_with_embeddings, copyable_settings, suggest_inputs, EMBEDDING_FIELD, SUGGEST_FIELD};
+use crate::query::{build_query, build_search_body, MultiSearchRequest, SearchOptions};
+use crate::query::hybrid::{reciprocal_rank_fusion, HybridOptions};
+use crate::query::more_like_this::{MoreLikeThisOptions, MoreLikeThisSource};
+use crate::query::relevance::RelevanceProfile;
+use crate::responses::alias_info::AliasInfo;
+use crate::responses::bulk_result::{BulkItemFailure, BulkResponse};
+use crate::responses::analyze_result::{AnalyzeResult, AnalyzeToken};
 use crate::responses::check_if_exist::CheckIfFileExistsResult;
-use crate::responses::index_info::IndexInfo;
-use crate::responses::search_result::{DistantElasticSearchResult};
+use crate::responses::cluster_health::{ClusterHealth, IndexHealth};
+use crate::responses::count_result::CountResult;
+use crate::responses::delete_by_query::DeleteByQueryReport;
+use crate::responses::index_info::{HealthStatus, IndexInfo};
+use crate::responses::index_stats::{IndexStats, IndicesStatsResponse};
+use crate::responses::removal_report::{RemovalFailure, RemovalReport};
+use crate::responses::hybrid_result::{ComponentScores, HybridSearchResult};
+use crate::responses::search_result::{DistantElasticSearchResult, HighlightTags, Hit, Hits, Total};
+use crate::responses::suggest_result::SuggestResult;
+use crate::responses::task::{TaskStatus, TaskSubmitted};
+use crate::responses::update_by_query::UpdateByQueryReport;
+use crate::request_options::{to_es_time, RequestOptions};
+use crate::retry::{RetryEvent, RetryPolicy};
 
 pub struct DistantClient {
     user_name: String,
@@ -25,8 +53,37 @@ pub struct DistantClient {
     endpoint: String,
     client: Elasticsearch,
     is_connected: bool,
+    // tenant namespace, every index name is prefixed with "<namespace>_" in the cluster
+    namespace: Option<String>,
+    retry_policy: RetryPolicy,
+    // client-wide bound on a single request, overridden by RequestOptions::timeout
+    request_timeout: Option<std::time::Duration>,
+    // populate the completion field when indexing, requires an index created with carrel_index_body
+    index_suggestions: bool,
+    // used by search() when no SearchOptions are given
+    search_options: SearchOptions,
+    // relevance profiles selectable by name in SearchOptions
+    relevance_profiles: HashMap<String, RelevanceProfile>,
+    // embeds passages at index time and queries in semantic_search
+    embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
+    // copies text fields into subfields analyzed for their detected language
+    language_analysis: Option<LanguageAnalysis>,
+    // synonyms and stopwords of the indices created by create_carrel_index
+    search_analysis: Option<SearchAnalysis>,
 }
 
+// name of the completion suggester in suggest requests
+const SUGGESTER_NAME: &str = "carrel_suggest";
+
+// number of entries sent per bulk request, cancellation is checked between requests
+const BULK_CHUNK_SIZE: usize = 500;
+
+// longest wait for a background task when the call sets no timeout
+const DEFAULT_TASK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+// confirmation token that must be passed to remove_all_indices
+pub const REMOVE_ALL_INDICES_CONFIRMATION: &str = "REMOVE ALL INDICES";
+
 
 impl DistantClient {
     pub fn new() -> Self {
@@ -36,6 +93,15 @@ impl DistantClient {
             endpoint: String::new(),
             client: Elasticsearch::default(),
             is_connected: false,
+            namespace: None,
+            retry_policy: RetryPolicy::default(),
+            request_timeout: None,
+            index_suggestions: false,
+            search_options: SearchOptions::default(),
+            relevance_profiles: HashMap::new(),
+            embedding_provider: None,
+            language_analysis: None,
+            search_analysis: None,
         }
     }
 
@@ -46,7 +112,213 @@ impl DistantClient {
             endpoint: endpoint.to_string(),
             client: Elasticsearch::default(),
             is_connected: false,
+            namespace: None,
+            retry_policy: RetryPolicy::default(),
+            request_timeout: None,
+            index_suggestions: false,
+            search_options: SearchOptions::default(),
+            relevance_profiles: HashMap::new(),
+            embedding_provider: None,
+            language_analysis: None,
+            search_analysis: None,
+        }
+    }
+
+    // the namespace cannot contain the "_" separator, so no namespace is a prefix of another namespace's index names
+    pub fn with_namespace(mut self, namespace: &str) -> Result<Self, DistantError> {
+        if namespace.is_empty() {
+            return Err(DistantError::UnsafeOperation("namespace is empty".to_string()));
+        }
+        if namespace.starts_with('.') || namespace.contains(|c| matches!(c, '_' | '*' | '?' | ',')) {
+            return Err(DistantError::UnsafeOperation(format!("namespace cannot start with \".\" or contain \"_\", \"*\", \"?\" or \",\": {}", namespace)));
+        }
+        self.namespace = Some(namespace.to_string());
+        Ok(self)
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_request_timeout(mut self, request_timeout: std::time::Duration) -> Self {
+        self.request_timeout = Some(request_timeout);
+        self
+    }
+
+    pub fn with_suggestions(mut self, index_suggestions: bool) -> Self {
+        self.index_suggestions = index_suggestions;
+        self
+    }
+
+    pub fn with_search_options(mut self, search_options: SearchOptions) -> Self {
+        self.search_options = search_options;
+        self
+    }
+
+    pub fn with_relevance_profile(mut self, name: &str, relevance_profile: RelevanceProfile) -> Self {
+        self.relevance_profiles.insert(name.to_string(), relevance_profile);
+        self
+    }
+
+    fn relevance_profile(&self, search_options: &SearchOptions) -> Result<Option<&RelevanceProfile>, DistantError> {
+        match &search_options.relevance_profile {
+            Some(name) => self.relevance_profiles
+                .get(name)
+                .map(Some)
+                .ok_or_else(|| DistantError::GeneralError(format!("Unknown relevance profile {}", name))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn with_embedding_provider(mut self, embedding_provider: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedding_provider = Some(embedding_provider);
+        self
+    }
+
+    pub fn with_language_analysis(mut self, language_analysis: LanguageAnalysis) -> Self {
+        self.language_analysis = Some(language_analysis);
+        self
+    }
+
+    pub fn with_search_analysis(mut self, search_analysis: SearchAnalysis) -> Self {
+        self.search_analysis = Some(search_analysis);
+        self
+    }
+
+    // document stored for the item, with the derived fields enabled on the client
+    fn document_body(&self, item: &CarrelSearchResultItem, embedding: Option<Vec<f32>>) -> Value {
+        let mut document = json!(item);
+        if self.index_suggestions {
+            document[SUGGEST_FIELD] = json!({
+                "input": suggest_inputs(item)
+            });
+        }
+        if let Some(embedding) = embedding {
+            document[EMBEDDING_FIELD] = json!(embedding);
+        }
+        if let Some(language) = self.language_analysis.as_ref().and_then(|language_analysis| language_analysis.detect(item)) {
+            for field in LANGUAGE_FIELDS {
+                if let Some(value) = document.get(field).cloned() {
+                    document[language_field(field, language)] = value;
+                }
+            }
         }
+        document
+    }
+
+    // embeddings of the entries when the client has an embedding provider
+    async fn embed_entries(&self, entries: &[ElasticInputEntry]) -> Result<Vec<Option<Vec<f32>>>, DistantError> {
+        let embedding_provider = match &self.embedding_provider {
+            Some(embedding_provider) => embedding_provider,
+            None => return Ok(entries.iter().map(|_| None).collect()),
+        };
+        // passages without text get no embedding, a zero vector would break cosine similarity
+        let texts: Vec<String> = entries.iter().map(|entry| embedding_text(&entry.item)).collect();
+        let positions: Vec<usize> = texts.iter()
+            .enumerate()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .map(|(position, _)| position)
+            .collect();
+        let non_empty_texts: Vec<String> = positions.iter().map(|position| texts[*position].clone()).collect();
+        let embeddings = embedding_provider.embed(&non_empty_texts).await?;
+        if embeddings.len() != non_empty_texts.len() {
+            return Err(DistantError::GeneralError(format!("Embedding provider returned {} vectors for {} texts", embeddings.len(), non_empty_texts.len())));
+        }
+        let mut entry_embeddings: Vec<Option<Vec<f32>>> = entries.iter().map(|_| None).collect();
+        for (position, embedding) in positions.into_iter().zip(embeddings) {
+            if is_usable_embedding(&embedding) {
+                entry_embeddings[position] = Some(embedding);
+            }
+        }
+        Ok(entry_embeddings)
+    }
+
+    fn timeout(&self, options: &RequestOptions) -> Option<std::time::Duration> {
+        options.timeout.or(self.request_timeout)
+    }
+
+    // bound the operation by the timeout and abort it when the cancellation token fires
+    async fn with_options<T>(&self, options: &RequestOptions, operation: impl Future<Output=Result<T, DistantError>>) -> Result<T, DistantError> {
+        let timeout = self.timeout(options);
+        let operation = async {
+            match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, operation)
+                    .await
+                    .unwrap_or_else(|_| Err(DistantError::Timeout(format!("request did not complete within {:?}", timeout)))),
+                None => operation.await,
+            }
+        };
+        match &options.cancellation_token {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(DistantError::Cancelled),
+                result = operation => result,
+            },
+            None => operation.await,
+        }
+    }
+
+    // send the request built by `send`, retrying transient failures according to the retry policy
+    async fn send_with_retry<F, Fut>(&self, operation: &str, idempotent: bool, mut send: F) -> Result<Response, DistantError>
+        where F: FnMut() -> Fut,
+              Fut: Future<Output=Result<Response, Error>>
+    {
+        let mut attempt = 1;
+        loop {
+            let result = send().await;
+            let reason = match &result {
+                Ok(response) if self.retry_policy.is_retryable_status(response.status_code().as_u16()) => {
+                    Some(format!("status {}", response.status_code()))
+                }
+                Ok(_) => None,
+                Err(e) => Some(e.to_string()),
+            };
+            let reason = match reason {
+                Some(reason) if self.retry_policy.should_retry(attempt, idempotent) => reason,
+                _ => return Ok(result?),
+            };
+            let delay = self.retry_policy.backoff(attempt);
+            warn!("{} attempt {} failed with {}, retrying in {:?}", operation, attempt, reason, delay);
+            if let Some(on_retry) = &self.retry_policy.on_retry {
+                on_retry(&RetryEvent { operation: operation.to_string(), attempt, delay, reason });
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    // full index name in the cluster for an index name local to the namespace
+    pub fn scoped_index(&self, index_name: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}_{}", namespace, index_name),
+            None => index_name.to_string(),
+        }
+    }
+
+    // index name local to the namespace, None if the index belongs to another namespace.
+    // the wildcard pattern of the namespace may match more than its indices, every name is checked here
+    pub fn unscoped_index<'a>(&self, index_name: &'a str) -> Option<&'a str> {
+        match &self.namespace {
+            Some(namespace) => index_name
+                .strip_prefix(namespace.as_str())
+                .and_then(|name| name.strip_prefix('_')),
+            None => Some(index_name),
+        }
+    }
+
+    // check that a destructive operation on the local index name cannot reach outside of the namespace
+    fn check_removable(&self, index_name: &str) -> Result<(), String> {
+        if index_name.is_empty() {
+            return Err("index name is empty".to_string());
+        }
+        if index_name == "_all" || index_name.contains(|c| c == '*' || c == '?' || c == ',') {
+            return Err(format!("wildcards are not allowed: {}", index_name));
+        }
+        if index_name.starts_with('.') {
+            return Err(format!("system indices cannot be removed: {}", index_name));
+        }
+        Ok(())
     }
 }
 
@@ -56,12 +328,40 @@ pub struct ElasticInputEntry {
     pub unique_id: String,
 }
 
+// where the documents of a new versioned index come from when reindexing behind an alias
+pub enum ReindexSource {
+    // copy the documents from the index currently behind the alias
+    CurrentIndex,
+    // ingest the given entries again
+    Entries(Vec<ElasticInputEntry>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReindexProgress {
+    CreatedIndex(String),
+    Copying { created: i64, total: i64 },
+    Ingested(usize),
+    SwappedAlias { alias: String, index: String },
+    RemovedIndex(String),
+    // the old index is still there although the alias moved, it can be removed with remove_index
+    RemoveFailed { index: String, reason: String },
+}
+
 // check health of the distant client
 impl DistantClient {
     pub async fn index(&self, index_name: &str, entries: Vec<ElasticInputEntry>) -> Result<(), DistantError> {
-        let mut bulk_body: Vec<JsonBody<_>> = Vec::new();
+        self.index_with_options(index_name, entries, &RequestOptions::default()).await
+    }
+
+    // index the entries in chunks of BULK_CHUNK_SIZE, stopping between chunks when cancelled.
+    // rejected items are retried, those that still fail are listed in BulkItemsFailed
+    pub async fn index_with_options(&self, index_name: &str, entries: Vec<ElasticInputEntry>, options: &RequestOptions) -> Result<(), DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let es_timeout = self.timeout(options).map(to_es_time);
+        let mut bulk_body: Vec<Value> = Vec::new();
+        let embeddings = self.embed_entries(&entries).await?;
 
-        for entry in entries {
+        for (entry, embedding) in entries.into_iter().zip(embeddings) {
             // Add the action metadata
             let action_metadata = json!({
                 "index": {
@@ -70,171 +370,1255 @@ impl DistantClient {
                     "_id": entry.unique_id,
                 }
             });
-            bulk_body.push(JsonBody::new(action_metadata));
+            bulk_body.push(action_metadata);
 
             // Add the document body
-            let document_body = json!(entry.item);
-            bulk_body.push(JsonBody::new(document_body));
+            let document_body = self.document_body(&entry.item, embedding);
+            bulk_body.push(document_body);
         }
 
-        let response = self.client
-            .bulk(BulkParts::Index(index_name))
-            .body(bulk_body)
-            .send().await;
-
-        println!("{:?}", response);
+        // each entry is an action line followed by a document line
+        let mut failures = Vec::new();
+        for (chunk_number, chunk) in bulk_body.chunks(BULK_CHUNK_SIZE * 2).enumerate() {
+            if options.is_cancelled() {
+                return Err(DistantError::Cancelled);
+            }
+            let chunk_failures = self.send_bulk_chunk(&index_name, chunk, options, es_timeout.as_deref()).await?;
+            failures.extend(chunk_failures.into_iter().map(|mut failure| {
+                failure.position += chunk_number * BULK_CHUNK_SIZE;
+                failure
+            }));
+        }
+        if !failures.is_empty() {
+            return Err(DistantError::BulkItemsFailed(failures));
+        }
         Ok(())
     }
-    pub async fn check_health(&self) -> Result<String, Error> {
-        let health = self.client.cat().health().send().await;
-        match health {
-            Ok(health) => {
-                let response_body = health.text().await;
-                response_body
+
+    // send one bulk request, resending the items rejected with a retryable status, and return the items that still failed
+    async fn send_bulk_chunk(&self, index_name: &str, chunk: &[Value], options: &RequestOptions, es_timeout: Option<&str>) -> Result<Vec<BulkItemFailure>, DistantError> {
+        // position in the chunk and action and document lines of the items still to send
+        let mut pending: Vec<(usize, &[Value])> = chunk.chunks(2).enumerate().collect();
+        let mut failed: Vec<BulkItemFailure> = Vec::new();
+        let mut attempt = 1;
+        loop {
+            let body: Vec<Value> = pending.iter().flat_map(|(_, lines)| lines.iter().cloned()).collect();
+            // documents are indexed with explicit ids, so sending the bulk again is safe
+            let response = self.with_options(options, self.send_with_retry("bulk", true, || {
+                let body: Vec<JsonBody<Value>> = body.iter().cloned().map(JsonBody::new).collect();
+                let mut request = self.client
+                    .bulk(BulkParts::Index(index_name))
+                    .body(body);
+                if let Some(es_timeout) = es_timeout {
+                    request = request.timeout(es_timeout);
+                }
+                request.send()
+            })).await?;
+            if response.status_code() != StatusCode::OK {
+                return Err(DistantError::from_response(response).await);
             }
-            Err(e) => {
-                println!("{:?}", e);
-                Err(e)
+            // a 200 response reports the rejected items individually
+            let (retryable, permanent): (Vec<BulkItemFailure>, Vec<BulkItemFailure>) = response
+                .json::<BulkResponse>().await?
+                .failures()
+                .into_iter()
+                .map(|mut failure| {
+                    failure.position = pending[failure.position].0;
+                    failure
+                })
+                .partition(|failure| self.retry_policy.is_retryable_status(failure.status));
+            failed.extend(permanent);
+            if retryable.is_empty() || !self.retry_policy.should_retry(attempt, true) || options.is_cancelled() {
+                failed.extend(retryable);
+                failed.sort_by_key(|failure| failure.position);
+                return Ok(failed);
+            }
+            let delay = self.retry_policy.backoff(attempt);
+            let reason = format!("{} rejected bulk items", retryable.len());
+            warn!("bulk attempt {} failed with {}, retrying in {:?}", attempt, reason, delay);
+            if let Some(on_retry) = &self.retry_policy.on_retry {
+                on_retry(&RetryEvent { operation: "bulk".to_string(), attempt, delay, reason });
             }
+            tokio::time::sleep(delay).await;
+            pending = retryable.iter()
+                .map(|failure| (failure.position, &chunk[failure.position * 2..failure.position * 2 + 2]))
+                .collect();
+            attempt += 1;
         }
     }
 
+    pub async fn check_health(&self) -> Result<ClusterHealth, DistantError> {
+        let health = self.client
+            .cluster()
+            .health(ClusterHealthParts::None)
+            .send().await?
+            .json::<ClusterHealth>().await?;
+        Ok(health)
+    }
+
+    // wait until the cluster reaches at least the given status, e.g. before ingestion
+    pub async fn wait_for_status(&self, status: HealthStatus, timeout: std::time::Duration) -> Result<ClusterHealth, DistantError> {
+        let wait_for_status = match status {
+            HealthStatus::Green => WaitForStatus::Green,
+            HealthStatus::Yellow => WaitForStatus::Yellow,
+            HealthStatus::Red => WaitForStatus::Red,
+        };
+        let timeout = format!("{}ms", timeout.as_millis());
+        let health = self.client
+            .cluster()
+            .health(ClusterHealthParts::None)
+            .wait_for_status(wait_for_status)
+            .timeout(&timeout)
+            .send().await?
+            .json::<ClusterHealth>().await?;
+        if health.timed_out {
+            return Err(DistantError::Timeout(format!("Cluster did not reach {:?} within {}, status is {:?}", status, timeout, health.status)));
+        }
+        Ok(health)
+    }
+
+    // health of a single index
+    pub async fn index_health(&self, index_name: &str) -> Result<IndexHealth, DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let mut health = self.client
+            .cluster()
+            .health(ClusterHealthParts::Index(&[index_name.as_str()]))
+            .level(Level::Indices)
+            .send().await?
+            .json::<ClusterHealth>().await?;
+        health.indices.remove(&index_name).ok_or_else(|| {
+            DistantError::GeneralError(format!("No health returned for {}", index_name))
+        })
+    }
+
     // search for documents in the elasticsearch index
     pub async fn search(&self,
                         index_name: String,
                         search_query: SearchQuery,
+    ) -> Result<DistantElasticSearchResult, DistantError> {
+        self.search_with_options(index_name, search_query, &self.search_options, &RequestOptions::default()).await
+    }
+
+    pub async fn search_with_options(&self,
+                                     index_name: String,
+                                     search_query: SearchQuery,
+                                     search_options: &SearchOptions,
+                                     options: &RequestOptions,
+    ) -> Result<DistantElasticSearchResult, DistantError> {
+        self.search_indices(&[index_name], search_query, search_options, options).await
+    }
+
+    // search several indices or aliases at once, each hit's index is the library it came from
+    pub async fn search_indices(&self,
+                                index_names: &[String],
+                                search_query: SearchQuery,
+                                search_options: &SearchOptions,
+                                options: &RequestOptions,
     ) -> Result<DistantElasticSearchResult, DistantError> {
         info!("Search query: {:?}", &search_query);
-        let filter = search_query.filter;
-        let mut query_text = String::new();
-        let mut query_fields = Vec::new();
-        match filter {
-            Some(filter) => {
-                query_text = filter.global_filter.unwrap_or("".to_string());
-                query_fields = filter.global_filter_fields;
+        let body_payload = self.search_body(&search_query, search_options)?;
+        let offset = search_query.offset;
+        let length = search_query.length;
+
+        info!("Search query payload: {:?}", &body_payload);
+
+        let scoped_names: Vec<String> = index_names.iter().map(|index_name| self.scoped_index(index_name)).collect();
+        let index_parts: Vec<&str> = scoped_names.iter().map(String::as_str).collect();
+        let index_parts = &index_parts[..];
+
+        let es_timeout = self.timeout(options).map(to_es_time);
+        let result = self.with_options(options, self.send_with_retry("search", true, || {
+            let mut request = self.client
+                .search(SearchParts::Index(index_parts))
+                .from(offset as i64)
+                .size(length as i64)
+                // .scroll("1d")
+                .body(body_payload.clone());
+            if let Some(es_timeout) = &es_timeout {
+                request = request.timeout(es_timeout);
+            }
+            request.send()
+        })).await?;
+        // match status code
+        match result.status_code() {
+            StatusCode::OK => {
+                let mut response_body: DistantElasticSearchResult = result.json::<DistantElasticSearchResult>().await?;
+                self.complete_search_result(&mut response_body, index_names, search_options);
+                Ok(response_body)
+            }
+            // catch and throw
+            _ => {
+                Err(DistantError::from_response(result).await)
             }
-            None => {}
         }
+    }
 
-        let sort = search_query.sort;
+    // passages closest in meaning to the global filter text of the query, by cosine similarity of the embeddings
+    pub async fn semantic_search(&self, index_name: &str, search_query: SearchQuery) -> Result<CarrelSearchResponse, DistantError> {
+        let result = self.semantic_search_result(index_name, &search_query, &RequestOptions::default()).await?;
+        Ok(result.into())
+    }
+
+    async fn semantic_search_result(&self,
+                                    index_name: &str,
+                                    search_query: &SearchQuery,
+                                    options: &RequestOptions,
+    ) -> Result<DistantElasticSearchResult, DistantError> {
+        let embedding_provider = self.embedding_provider
+            .as_ref()
+            .ok_or_else(|| DistantError::GeneralError("No embedding provider configured".to_string()))?;
+        let query_text = search_query.filter
+            .as_ref()
+            .and_then(|filter| filter.global_filter.clone())
+            .unwrap_or_default();
+        if query_text.trim().is_empty() {
+            return Err(DistantError::InvalidQuery("semantic search needs a query text".to_string()));
+        }
+        let query_vector = embedding_provider.embed(&[query_text.clone()]).await?
+            .pop()
+            .ok_or_else(|| DistantError::GeneralError("Embedding provider returned no vector".to_string()))?;
+        if !is_usable_embedding(&query_vector) {
+            return Err(DistantError::InvalidQuery(format!("no embedding could be computed for \"{}\"", query_text)));
+        }
 
-        let sort_json = match sort {
-            Some(sort) => {
-                let sort_field = sort.field;
-                let sort_order = sort.order;
-                json!({ sort_field: { "order": sort_order } })
+        let body_payload = json!({
+            "size": search_query.length,
+            "from": search_query.offset,
+            "_source": { "excludes": [EMBEDDING_FIELD] },
+            "query": {
+                "script_score": {
+                    "query": { "exists": { "field": EMBEDDING_FIELD } },
+                    "script": {
+                        // cosine similarity is in [-1, 1], scores must not be negative
+                        "source": format!("doc['{0}'].size() == 0 ? 0 : cosineSimilarity(params.query_vector, '{0}') + 1.0", EMBEDDING_FIELD),
+                        "params": { "query_vector": query_vector }
+                    }
+                }
+            }
+        });
+        let scoped_name = self.scoped_index(index_name);
+        let index_parts = &[scoped_name.as_str()];
+        let result = self.with_options(options, self.send_with_retry("semantic_search", true, || {
+            self.client
+                .search(SearchParts::Index(index_parts))
+                .body(body_payload.clone())
+                .send()
+        })).await?;
+        match result.status_code() {
+            StatusCode::OK => {
+                let mut response_body = result.json::<DistantElasticSearchResult>().await?;
+                self.complete_search_result(&mut response_body, &[index_name.to_string()], &self.search_options);
+                Ok(response_body)
             }
-            None => {
-                json!({})
+            _ => Err(DistantError::from_response(result).await),
+        }
+    }
+
+    // keyword and semantic search fused with reciprocal rank fusion, the total is a lower bound
+    pub async fn hybrid_search(&self,
+                               index_name: &str,
+                               search_query: SearchQuery,
+                               hybrid_options: &HybridOptions,
+    ) -> Result<HybridSearchResult, DistantError> {
+        // the window covers at least the requested page, later pages are cut from a larger fused ranking
+        let page_end = search_query.offset as u32 + search_query.length as u32;
+        let mut window_query = search_query.clone();
+        window_query.offset = 0;
+        window_query.length = hybrid_options.window_size.max(page_end) as _;
+        let options = RequestOptions::default();
+        let (lexical, semantic) = futures::try_join!(
+            self.search_with_options(index_name.to_string(), window_query.clone(), &self.search_options, &options),
+            self.semantic_search_result(index_name, &window_query, &options),
+        )?;
+
+        fn hit_key(hit: &Hit) -> String {
+            format!("{}/{}", hit.index, hit.id)
+        }
+        let lexical_keys: Vec<String> = lexical.hits.hits.iter().map(hit_key).collect();
+        let semantic_keys: Vec<String> = semantic.hits.hits.iter().map(hit_key).collect();
+        let lexical_scores: Vec<f64> = lexical.hits.hits.iter().map(|hit| hit.score).collect();
+        let semantic_scores: Vec<f64> = semantic.hits.hits.iter().map(|hit| hit.score).collect();
+        let fused = reciprocal_rank_fusion(&lexical_keys, &semantic_keys, hybrid_options);
+
+        // lexical hits replace semantic ones as only they carry highlights
+        let mut hits: HashMap<String, Hit> = semantic.hits.hits.into_iter().map(|hit| (hit_key(&hit), hit)).collect();
+        hits.extend(lexical.hits.hits.into_iter().map(|hit| (hit_key(&hit), hit)));
+
+        // at least every fused hit and every keyword match, semantic matches beyond the window are unknown
+        let total = (fused.len() as i64).max(lexical.hits.total.value);
+        let mut page_hits = Vec::new();
+        let mut component_scores = Vec::new();
+        for rank in fused.into_iter().skip(search_query.offset as usize).take(search_query.length as usize) {
+            if let Some(mut hit) = hits.remove(&rank.key) {
+                hit.score = rank.score;
+                page_hits.push(hit);
+                component_scores.push(ComponentScores {
+                    fused_score: rank.score,
+                    lexical_score: rank.lexical_rank.map(|rank| lexical_scores[rank]),
+                    lexical_rank: rank.lexical_rank,
+                    // the script score is shifted by one to stay positive
+                    semantic_score: rank.semantic_rank.map(|rank| semantic_scores[rank] - 1.0),
+                    semantic_rank: rank.semantic_rank,
+                });
             }
+        }
+
+        let result = DistantElasticSearchResult {
+            scroll_id: None,
+            shards: lexical.shards,
+            hits: Hits {
+                max_score: page_hits.first().map(|hit| hit.score),
+                hits: page_hits,
+                total: Total { relation: "gte".to_string(), value: total },
+            },
+            timed_out: lexical.timed_out || semantic.timed_out,
+            took: lexical.took.max(semantic.took),
+            aggregations: lexical.aggregations,
+            highlight_tags: lexical.highlight_tags,
         };
+        Ok(HybridSearchResult { result, component_scores })
+    }
 
-        let offset = search_query.offset;
-        let length = search_query.length;
+    // passages related to a passage or a text, the source passage itself is excluded
+    pub async fn more_like_this(&self,
+                                index_name: &str,
+                                source: MoreLikeThisSource,
+                                more_like_this_options: &MoreLikeThisOptions,
+    ) -> Result<CarrelSearchResponse, DistantError> {
+        let scoped_name = self.scoped_index(index_name);
+        let index_parts = &[scoped_name.as_str()];
+        let body_payload = self.more_like_this_body(&scoped_name, &source, more_like_this_options);
+        info!("More like this payload: {:?}", &body_payload);
+        let result = self.send_with_retry("more_like_this", true, || {
+            self.client
+                .search(SearchParts::Index(index_parts))
+                .body(body_payload.clone())
+                .send()
+        }).await?;
+        match result.status_code() {
+            StatusCode::OK => {
+                let mut response_body = result.json::<DistantElasticSearchResult>().await?;
+                self.complete_search_result(&mut response_body, &[index_name.to_string()], &self.search_options);
+                Ok(response_body.into())
+            }
+            _ => Err(DistantError::from_response(result).await),
+        }
+    }
+
+    fn more_like_this_body(&self, scoped_name: &str, source: &MoreLikeThisSource, more_like_this_options: &MoreLikeThisOptions) -> Value {
+        json!({
+            "size": more_like_this_options.size,
+            "query": more_like_this_options.to_query(scoped_name, source),
+            "highlight": self.search_options.highlight.to_json()
+        })
+    }
 
+    // exact number of documents matching the query, translated the same way as in search()
+    pub async fn count(&self, index_name: &str, search_query: SearchQuery) -> Result<u64, DistantError> {
+        self.count_with_options(index_name, search_query, &self.search_options, &RequestOptions::default()).await
+    }
+
+    pub async fn count_with_options(&self,
+                                    index_name: &str,
+                                    search_query: SearchQuery,
+                                    search_options: &SearchOptions,
+                                    options: &RequestOptions,
+    ) -> Result<u64, DistantError> {
+        let relevance = self.relevance_profile(search_options)?;
         let body_payload = json!({
-                "size": length,
-                "from": offset,
-                "query": {
-                    "multi_match": {
-                        "query": query_text,
-                        "fields": query_fields,
-                        "fuzziness": "AUTO"
-                    }
-                },
-                "sort": [sort_json],
-                "highlight": {
-                    "require_field_match": false,
-                    "fields": {
-                        "*": {
-                            "pre_tags": ["<em>"],
-                            "post_tags": ["</em>"]
-                        }
-                    }
+            "query": build_query(&search_query, &self.with_client_languages(search_options), relevance)
+        });
+        let index_name = self.scoped_index(index_name);
+        let index_parts = &[index_name.as_str()];
+        let result = self.with_options(options, self.send_with_retry("count", true, || {
+            self.client
+                .count(CountParts::Index(index_parts))
+                .body(body_payload.clone())
+                .send()
+        })).await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(result.json::<CountResult>().await?.count),
+            _ => Err(DistantError::from_response(result).await),
+        }
+    }
+
+    // run several independent searches in one round-trip, each search succeeds or fails on its own
+    pub async fn msearch(&self,
+                         requests: Vec<MultiSearchRequest>,
+                         options: &RequestOptions,
+    ) -> Result<Vec<Result<DistantElasticSearchResult, DistantError>>, DistantError> {
+        let (msearch_body, request_errors) = self.msearch_body(&requests);
+        info!("Multi search payload: {:?}", &msearch_body);
+        if msearch_body.is_empty() {
+            return Ok(self.msearch_results(&requests, request_errors, vec![]));
+        }
+
+        let result = self.with_options(options, self.send_with_retry("msearch", true, || {
+            let msearch_body: Vec<JsonBody<Value>> = msearch_body.iter().cloned().map(JsonBody::new).collect();
+            self.client
+                .msearch(MsearchParts::None)
+                .body(msearch_body)
+                .send()
+        })).await?;
+        if result.status_code() != StatusCode::OK {
+            return Err(DistantError::from_response(result).await);
+        }
+        let mut response_body = result.json::<Value>().await?;
+        let responses = match response_body["responses"].take() {
+            Value::Array(responses) => responses,
+            _ => return Err(DistantError::GeneralError("Multi search response has no responses".to_string())),
+        };
+        Ok(self.msearch_results(&requests, request_errors, responses))
+    }
+
+    // header and body lines of the valid requests, and the error of each request that could not be built
+    fn msearch_body(&self, requests: &[MultiSearchRequest]) -> (Vec<Value>, Vec<Option<DistantError>>) {
+        let mut msearch_body: Vec<Value> = Vec::new();
+        let mut request_errors = Vec::new();
+        for request in requests {
+            match self.search_body(&request.search_query, &request.search_options) {
+                Ok(body) => {
+                    let index_names: Vec<String> = request.index_names.iter().map(|index_name| self.scoped_index(index_name)).collect();
+                    msearch_body.push(json!({ "index": index_names }));
+                    msearch_body.push(body);
+                    request_errors.push(None);
                 }
-            });
+                Err(e) => request_errors.push(Some(e)),
+            }
+        }
+        (msearch_body, request_errors)
+    }
 
-        info!("Search query payload: {:?}", &body_payload);
+    // one result per request: its build error, or the next response of the sent requests
+    fn msearch_results(&self,
+                       requests: &[MultiSearchRequest],
+                       request_errors: Vec<Option<DistantError>>,
+                       responses: Vec<Value>,
+    ) -> Vec<Result<DistantElasticSearchResult, DistantError>> {
+        let mut responses = responses.into_iter();
+        requests.iter()
+            .zip(request_errors)
+            .map(|(request, request_error)| {
+                if let Some(e) = request_error {
+                    return Err(e);
+                }
+                let response = responses.next().ok_or_else(|| {
+                    DistantError::GeneralError("Multi search response has fewer responses than requests".to_string())
+                })?;
+                if response.get("error").is_some() {
+                    let status = response["status"].as_u64().unwrap_or(500) as u16;
+                    return Err(DistantError::from_error_body(status, &response.to_string()));
+                }
+                let mut search_result = serde_json::from_value::<DistantElasticSearchResult>(response)?;
+                self.complete_search_result(&mut search_result, &request.index_names, &request.search_options);
+                Ok(search_result)
+            })
+            .collect()
+    }
 
-        let reques_body_json = serde_json::to_string_pretty(&body_payload).unwrap();
+    // the options searching the language subfields of the client's language analysis, unless they name languages
+    fn with_client_languages<'a>(&self, search_options: &'a SearchOptions) -> Cow<'a, SearchOptions> {
+        match &self.language_analysis {
+            Some(language_analysis) if search_options.languages.is_empty() => {
+                Cow::Owned(search_options.clone().with_languages(language_analysis.languages()))
+            }
+            _ => Cow::Borrowed(search_options),
+        }
+    }
 
-// Assuming index_name is a String
+    // the search request body with the relevance profile and index boosts of the options
+    fn search_body(&self, search_query: &SearchQuery, search_options: &SearchOptions) -> Result<Value, DistantError> {
+        let relevance = self.relevance_profile(search_options)?;
+        let mut body_payload = build_search_body(search_query, &self.with_client_languages(search_options), relevance);
+        if !search_options.index_boosts.is_empty() {
+            body_payload["indices_boost"] = search_options.index_boosts.iter()
+                .map(|(index_name, boost)| json!({ self.scoped_index(index_name): boost }))
+                .collect();
+        }
+        Ok(body_payload)
+    }
+
+    // record the highlight tags and report the library of each hit as it was searched:
+    // without the namespace, and by alias rather than the versioned index behind it
+    fn complete_search_result(&self, search_result: &mut DistantElasticSearchResult, index_names: &[String], search_options: &SearchOptions) {
+        search_result.highlight_tags = HighlightTags {
+            pre_tag: search_options.highlight.pre_tag.clone(),
+            post_tag: search_options.highlight.post_tag.clone(),
+        };
+        for hit in search_result.hits.hits.iter_mut() {
+            if let Some(library) = self.unscoped_index(&hit.index) {
+                hit.index = library_name(library, index_names).to_string();
+            }
+        }
+    }
+
+    // remove a single index, the confirmation must repeat the index name
+    pub async fn remove_index(&self, index_name: String, confirmation: &str) -> Result<RemovalReport, DistantError> {
+        if confirmation != index_name {
+            return Err(DistantError::UnsafeOperation(format!("confirmation does not match index name {}", index_name)));
+        }
+        self.check_removable(&index_name).map_err(DistantError::UnsafeOperation)?;
+        let mut report = RemovalReport::default();
+        match self.delete_index(&self.scoped_index(&index_name)).await {
+            Ok(()) => report.removed.push(index_name),
+            Err(reason) => report.failed.push(RemovalFailure { index: index_name, reason }),
+        }
+        Ok(report)
+    }
+
+    // remove every index in the namespace
+    pub async fn remove_all_indices(&self, confirmation: &str) -> Result<RemovalReport, DistantError> {
+        if confirmation != REMOVE_ALL_INDICES_CONFIRMATION {
+            return Err(DistantError::UnsafeOperation(format!("confirmation must be \"{}\"", REMOVE_ALL_INDICES_CONFIRMATION)));
+        }
+        if self.namespace.as_deref().map_or(true, str::is_empty) {
+            return Err(DistantError::UnsafeOperation("a namespace is required to remove all indices".to_string()));
+        }
+        let mut report = RemovalReport::default();
+        let all_indices = self.list_indices().await?;
+        for index in all_indices {
+            let index_name = index.index;
+            let result = match self.check_removable(&index_name) {
+                Ok(()) => self.delete_index(&self.scoped_index(&index_name)).await,
+                Err(reason) => Err(reason),
+            };
+            match result {
+                Ok(()) => report.removed.push(index_name),
+                Err(reason) => report.failed.push(RemovalFailure { index: index_name, reason }),
+            }
+        }
+        Ok(report)
+    }
+
+    async fn delete_index(&self, index_name: &str) -> Result<(), String> {
+        let result = self.client
+            .indices()
+            .delete(IndicesDeleteParts::Index(&[index_name]))
+            .send().await
+            .map_err(|e| e.to_string())?;
+        match result.status_code() {
+            StatusCode::OK => Ok(()),
+            status => {
+                let body = result.text().await.unwrap_or_default();
+                Err(format!("{}: {}", status, body))
+            }
+        }
+    }
+
+    // delete a single document by its id, returns false if the document did not exist
+    pub async fn delete_document(&self, index_name: &str, id: &str) -> Result<bool, DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let result = self.send_with_retry("delete", true, || {
+            self.client
+                .delete(DeleteParts::IndexId(&index_name, id))
+                .send()
+        }).await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            _ => {
+                Err(DistantError::from_response(result).await)
+            }
+        }
+    }
+
+    // delete all passages indexed from the given file
+    pub async fn delete_by_file_path(&self, index_name: &str, file_path: &str) -> Result<DeleteByQueryReport, DistantError> {
+        self.delete_by_query(index_name, json!({
+            "term": {
+                "filePath.keyword": file_path
+            }
+        })).await
+    }
+
+    // delete all documents matching the query, the query is the content of the "query" key of the request body
+    pub async fn delete_by_query(&self, index_name: &str, query: Value) -> Result<DeleteByQueryReport, DistantError> {
+        self.delete_by_query_with_options(index_name, query, &RequestOptions::default()).await
+    }
+
+    // delete by query, cancelling the server-side task when the options are cancelled or time out
+    pub async fn delete_by_query_with_options(&self, index_name: &str, query: Value, options: &RequestOptions) -> Result<DeleteByQueryReport, DistantError> {
+        let index_name = self.scoped_index(index_name);
+        info!("Delete by query on {}: {:?}", index_name, &query);
         let index_parts = &[index_name.as_str()];
+        let result = self.with_options(options, self.send_with_retry("delete_by_query", true, || {
+            self.client
+                .delete_by_query(DeleteByQueryParts::Index(index_parts))
+                .conflicts(Conflicts::Proceed)
+                .refresh(true)
+                .wait_for_completion(false)
+                .body(json!({
+                    "query": query
+                }))
+                .send()
+        })).await?;
+        match result.status_code() {
+            StatusCode::OK => {
+                let submitted = result.json::<TaskSubmitted>().await?;
+                let response = self.wait_for_task(&submitted.task, options).await?;
+                Ok(serde_json::from_value::<DeleteByQueryReport>(response)?)
+            }
+            _ => {
+                Err(DistantError::from_response(result).await)
+            }
+        }
+    }
 
-        let request_body = self.client
-            .search(SearchParts::Index(index_parts))
-            .from(offset as i64)
-            .size(length as i64)
-            // .scroll("1d")
-            .body(body_payload);
+    // merge the partial document into the stored document
+    pub async fn update_document(&self, index_name: &str, id: &str, partial_doc: Value) -> Result<(), DistantError> {
+        self.send_update(index_name, id, json!({
+            "doc": partial_doc
+        })).await
+    }
 
-        let result = request_body.send()
-            .await?;
-        // match status code
+    // update the stored document with a painless script
+    pub async fn update_document_with_script(&self, index_name: &str, id: &str, script: &str, params: Value) -> Result<(), DistantError> {
+        self.send_update(index_name, id, json!({
+            "script": {
+                "source": script,
+                "lang": "painless",
+                "params": params
+            }
+        })).await
+    }
+
+    async fn send_update(&self, index_name: &str, id: &str, body: Value) -> Result<(), DistantError> {
+        let index_name = self.scoped_index(index_name);
+        // merging a partial document is idempotent, running a script again is not
+        let idempotent = body.get("doc").is_some();
+        let result = self.send_with_retry("update", idempotent, || {
+            self.client
+                .update(UpdateParts::IndexId(&index_name, id))
+                .retry_on_conflict(3)
+                .body(body.clone())
+                .send()
+        }).await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(()),
+            _ => {
+                Err(DistantError::from_response(result).await)
+            }
+        }
+    }
+
+    // add tags to every passage of the given file, tags already present are kept once
+    pub async fn add_tags_by_file_path(&self, index_name: &str, file_path: &str, tags: &[String]) -> Result<UpdateByQueryReport, DistantError> {
+        self.update_by_query(index_name, json!({
+            "term": {
+                "filePath.keyword": file_path
+            }
+        }), json!({
+            "source": "if (ctx._source.tags == null) { ctx._source.tags = []; } \
+                       for (tag in params.tags) { if (!ctx._source.tags.contains(tag)) { ctx._source.tags.add(tag); } }",
+            "lang": "painless",
+            "params": {
+                "tags": tags
+            }
+        })).await
+    }
+
+    // remove tags from every passage of the given file
+    pub async fn remove_tags_by_file_path(&self, index_name: &str, file_path: &str, tags: &[String]) -> Result<UpdateByQueryReport, DistantError> {
+        self.update_by_query(index_name, json!({
+            "term": {
+                "filePath.keyword": file_path
+            }
+        }), json!({
+            "source": "if (ctx._source.tags != null) { ctx._source.tags.removeIf(tag -> params.tags.contains(tag)); }",
+            "lang": "painless",
+            "params": {
+                "tags": tags
+            }
+        })).await
+    }
+
+    // run the script on all documents matching the query
+    pub async fn update_by_query(&self, index_name: &str, query: Value, script: Value) -> Result<UpdateByQueryReport, DistantError> {
+        self.update_by_query_with_options(index_name, query, script, &RequestOptions::default()).await
+    }
+
+    pub async fn update_by_query_with_options(&self, index_name: &str, query: Value, script: Value, options: &RequestOptions) -> Result<UpdateByQueryReport, DistantError> {
+        let index_name = self.scoped_index(index_name);
+        info!("Update by query on {}: {:?}, script: {:?}", index_name, &query, &script);
+        let index_parts = &[index_name.as_str()];
+        let result = self.with_options(options, self.send_with_retry("update_by_query", false, || {
+            self.client
+                .update_by_query(UpdateByQueryParts::Index(index_parts))
+                .conflicts(Conflicts::Proceed)
+                .refresh(true)
+                .wait_for_completion(false)
+                .body(json!({
+                    "query": query,
+                    "script": script
+                }))
+                .send()
+        })).await?;
         match result.status_code() {
             StatusCode::OK => {
-                let response_body: DistantElasticSearchResult = result.json::<DistantElasticSearchResult>().await?;
-                Ok(response_body)
+                let submitted = result.json::<TaskSubmitted>().await?;
+                let response = self.wait_for_task(&submitted.task, options).await?;
+                Ok(serde_json::from_value::<UpdateByQueryReport>(response)?)
+            }
+            _ => {
+                Err(DistantError::from_response(result).await)
+            }
+        }
+    }
+
+    // poll a task started with wait_for_completion=false until it completes, returns the task response
+    async fn wait_for_task(&self, task_id: &str, options: &RequestOptions) -> Result<Value, DistantError> {
+        self.wait_for_task_with_progress(task_id, options, |_| {}).await
+    }
+
+    // only the per-call timeout bounds a task, the client-wide timeout is meant for single requests
+    async fn wait_for_task_with_progress(&self, task_id: &str, options: &RequestOptions, mut on_status: impl FnMut(&TaskStatus)) -> Result<Value, DistantError> {
+        // without a per-call timeout the task is still bounded, it is never polled forever
+        let timeout = options.timeout.unwrap_or(DEFAULT_TASK_TIMEOUT);
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if options.is_cancelled() {
+                self.cancel_task_after(task_id, "cancellation").await;
+                return Err(DistantError::Cancelled);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                self.cancel_task_after(task_id, "timeout").await;
+                return Err(DistantError::Timeout(format!("task {} did not complete within {:?}", task_id, timeout)));
+            }
+            let status = self.client
+                .tasks()
+                .get(TasksGetParts::TaskId(task_id))
+                .send().await?
+                .json::<TaskStatus>().await?;
+            on_status(&status);
+            if status.completed {
+                if let Some(error) = status.error {
+                    return Err(DistantError::GeneralError(format!("Task {} failed: {}", task_id, error)));
+                }
+                return Ok(status.response.unwrap_or(Value::Null));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+    }
+
+    // cancel the task on the way out of waiting, a failure is logged so that it does not hide the reason for giving up
+    async fn cancel_task_after(&self, task_id: &str, reason: &str) {
+        if let Err(e) = self.cancel_task(task_id).await {
+            warn!("Failed to cancel task {} after {}: {}", task_id, reason, e);
+        }
+    }
+
+    async fn cancel_task(&self, task_id: &str) -> Result<(), DistantError> {
+        info!("Cancelling task {}", task_id);
+        let result = self.client
+            .tasks()
+            .cancel(TasksCancelParts::TaskId(task_id))
+            .send().await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(()),
+            _ => Err(DistantError::from_response(result).await),
+        }
+    }
+
+    // create an index with the mappings for CarrelSearchResultItem documents, including the suggest field
+    pub async fn create_carrel_index(&self, index_name: &str) -> Result<(), DistantError> {
+        self.create_index(index_name, self.with_client_analysis(carrel_index_body())).await
+    }
+
+    // create an index for CarrelSearchResultItem documents with an embedding field sized for the embedding provider
+    pub async fn create_carrel_index_with_embeddings(&self, index_name: &str) -> Result<(), DistantError> {
+        let dimensions = self.embedding_provider
+            .as_ref()
+            .map(|embedding_provider| embedding_provider.dimensions())
+            .ok_or_else(|| DistantError::GeneralError("No embedding provider configured".to_string()))?;
+        self.create_index(index_name, self.with_client_analysis(carrel_index_body_with_embeddings(dimensions))).await
+    }
+
+    // the index body with the language subfields and search analysis configured on the client
+    fn with_client_analysis(&self, mut body: Value) -> Value {
+        if let Some(language_analysis) = &self.language_analysis {
+            add_language_fields(&mut body, language_analysis);
+        }
+        if let Some(search_analysis) = &self.search_analysis {
+            add_search_analysis(&mut body, search_analysis);
+        }
+        body
+    }
+
+    // suggestions for the prefix typed in the search box, best first
+    pub async fn suggest(&self, index_name: &str, prefix: &str, size: usize) -> Result<Vec<String>, DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let index_parts = &[index_name.as_str()];
+        let body_payload = json!({
+            "_source": false,
+            "suggest": {
+                SUGGESTER_NAME: {
+                    "prefix": prefix,
+                    "completion": {
+                        "field": SUGGEST_FIELD,
+                        "size": size,
+                        "skip_duplicates": true,
+                        "fuzzy": {
+                            "fuzziness": "AUTO"
+                        }
+                    }
+                }
+            }
+        });
+        let result = self.send_with_retry("suggest", true, || {
+            self.client
+                .search(SearchParts::Index(index_parts))
+                .body(body_payload.clone())
+                .send()
+        }).await?;
+        match result.status_code() {
+            StatusCode::OK => {
+                let suggest_result = result.json::<SuggestResult>().await?;
+                Ok(suggest_result.ranked_texts(SUGGESTER_NAME))
             }
-            // catch and throw
             _ => {
-                Err(DistantError::GeneralError("Error in search, check index name".to_string()))
+                Err(DistantError::from_response(result).await)
             }
         }
     }
 
-    pub async fn remove_index(&self, index_name: String) -> Result<(), DistantError> {
-        let _result = self.client
+    // create an index with the given settings and mappings body
+    pub async fn create_index(&self, index_name: &str, body: Value) -> Result<(), DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let result = self.client
             .indices()
-            .delete(IndicesDeleteParts::Index(&[index_name.as_str()]))
+            .create(IndicesCreateParts::Index(&index_name))
+            .body(body)
             .send().await?;
-        Ok(())
+        match result.status_code() {
+            StatusCode::OK => Ok(()),
+            _ => {
+                Err(DistantError::from_response(result).await)
+            }
+        }
     }
 
+    // replace the synonyms and stopwords behind the alias. analysis settings cannot change on an open index,
+    // so the index is rebuilt with reindex_with_alias and stays searchable throughout. edits to a synonyms
+    // file set with SearchAnalysis::with_synonyms_path only need reload_search_analyzers
+    pub async fn set_search_analysis(&self,
+                                     alias: &str,
+                                     search_analysis: &SearchAnalysis,
+                                     on_progress: impl FnMut(ReindexProgress),
+    ) -> Result<String, DistantError> {
+        let scoped_alias = self.scoped_index(alias);
+        let result = self.client
+            .indices()
+            .get_mapping(IndicesGetMappingParts::Index(&[scoped_alias.as_str()]))
+            .send().await?;
+        let mapping = self.single_index_entry(alias, result).await?;
+        let result = self.client
+            .indices()
+            .get_settings(IndicesGetSettingsParts::Index(&[scoped_alias.as_str()]))
+            .send().await?;
+        let settings = self.single_index_entry(alias, result).await?;
+        // keep shards, replicas and other analysis of the current index, only the search analyzer changes
+        let mut index_body = json!({
+            "settings": copyable_settings(&settings["settings"]),
+            "mappings": mapping["mappings"]
+        });
+        add_search_analysis(&mut index_body, search_analysis);
+        self.reindex_with_alias(alias, index_body, ReindexSource::CurrentIndex, on_progress).await
+    }
 
-    pub async fn remove_all_indices(&self) -> Result<(), DistantError> {
-        let all_indices = self.list_indices().await?;
-        for index in all_indices {
-            let index_name = index.index;
-            self.remove_index(index_name).await?;
+    // reload search analyzers whose synonyms come from an updateable synonyms file on the nodes
+    pub async fn reload_search_analyzers(&self, index_name: &str) -> Result<(), DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let result = self.client
+            .indices()
+            .reload_search_analyzers(IndicesReloadSearchAnalyzersParts::Index(&[index_name.as_str()]))
+            .send().await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(()),
+            _ => Err(DistantError::from_response(result).await),
+        }
+    }
+
+    // the synonyms and stopwords of the index or alias, empty when none were set
+    pub async fn get_search_analysis(&self, index_name: &str) -> Result<SearchAnalysis, DistantError> {
+        let scoped_name = self.scoped_index(index_name);
+        let result = self.client
+            .indices()
+            .get_settings(IndicesGetSettingsParts::Index(&[scoped_name.as_str()]))
+            .send().await?;
+        let settings = self.single_index_entry(index_name, result).await?;
+        Ok(SearchAnalysis::from_settings(&settings["settings"]))
+    }
+
+    // the entry of the index in a response keyed by index name, an alias is answered with the index behind it
+    async fn single_index_entry(&self, index_name: &str, result: Response) -> Result<Value, DistantError> {
+        if result.status_code() != StatusCode::OK {
+            return Err(DistantError::from_response(result).await);
+        }
+        let entries = result.json::<Map<String, Value>>().await?;
+        if entries.len() > 1 {
+            return Err(DistantError::GeneralError(format!("{} points to several indices", index_name)));
+        }
+        entries.into_iter()
+            .next()
+            .map(|(_, entry)| entry)
+            .ok_or_else(|| DistantError::IndexNotFound(index_name.to_string()))
+    }
+
+    // the tokens the analyzer of the index produces for the text, e.g. to check synonyms with SEARCH_ANALYZER
+    pub async fn analyze(&self, index_name: &str, text: &str, analyzer: &str) -> Result<Vec<AnalyzeToken>, DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let result = self.client
+            .indices()
+            .analyze(IndicesAnalyzeParts::Index(&index_name))
+            .body(json!({
+                "analyzer": analyzer,
+                "text": text
+            }))
+            .send().await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(result.json::<AnalyzeResult>().await?.tokens),
+            _ => Err(DistantError::from_response(result).await),
+        }
+    }
+
+    // point the alias at the index
+    pub async fn create_alias(&self, index_name: &str, alias: &str) -> Result<(), DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let alias = self.scoped_index(alias);
+        let result = self.client
+            .indices()
+            .put_alias(IndicesPutAliasParts::IndexName(&[index_name.as_str()], &alias))
+            .send().await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(()),
+            _ => {
+                Err(DistantError::from_response(result).await)
+            }
+        }
+    }
+
+    // atomically move the alias from one index to another
+    pub async fn swap_alias(&self, alias: &str, from_index: &str, to_index: &str) -> Result<(), DistantError> {
+        let alias = self.scoped_index(alias);
+        let from_index = self.scoped_index(from_index);
+        let to_index = self.scoped_index(to_index);
+        let result = self.client
+            .indices()
+            .update_aliases()
+            .body(json!({
+                "actions": [
+                    { "remove": { "index": from_index, "alias": alias } },
+                    { "add": { "index": to_index, "alias": alias } }
+                ]
+            }))
+            .send().await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(()),
+            _ => {
+                Err(DistantError::from_response(result).await)
+            }
+        }
+    }
+
+    // list the aliases of the namespace, alias and index names are local to the namespace
+    pub async fn list_aliases(&self) -> Result<Vec<AliasInfo>, DistantError> {
+        let pattern = self.scoped_index("*");
+        let patterns = [pattern.as_str()];
+        let alias_parts = match self.namespace {
+            Some(_) => CatAliasesParts::Name(&patterns),
+            None => CatAliasesParts::None,
+        };
+        let aliases = self.client
+            .cat()
+            .aliases(alias_parts)
+            .format("json")
+            .send().await?
+            .json::<Vec<AliasInfo>>().await?;
+        Ok(aliases
+            .into_iter()
+            .filter_map(|mut alias| {
+                alias.alias = self.unscoped_index(&alias.alias)?.to_string();
+                alias.index = self.unscoped_index(&alias.index)?.to_string();
+                Some(alias)
+            })
+            .collect())
+    }
+
+    // local names of the indices the alias points to, empty if the alias does not exist
+    pub async fn get_alias_indices(&self, alias: &str) -> Result<Vec<String>, DistantError> {
+        let alias = self.scoped_index(alias);
+        let result = self.client
+            .indices()
+            .get_alias(IndicesGetAliasParts::Name(&[alias.as_str()]))
+            .send().await?;
+        match result.status_code() {
+            StatusCode::OK => {
+                let indices = result.json::<Map<String, Value>>().await?;
+                Ok(indices
+                    .keys()
+                    .filter_map(|index| self.unscoped_index(index))
+                    .map(str::to_string)
+                    .collect())
+            }
+            StatusCode::NOT_FOUND => Ok(vec![]),
+            _ => {
+                Err(DistantError::from_response(result).await)
+            }
+        }
+    }
+
+    // rebuild the index behind the alias without downtime:
+    // create "<alias>_v<n+1>" with the body, fill it, swap the alias and remove the old index.
+    // an index named like the alias, created before aliasing, is replaced by the alias in the same step
+    pub async fn reindex_with_alias(&self,
+                                    alias: &str,
+                                    index_body: Value,
+                                    source: ReindexSource,
+                                    on_progress: impl FnMut(ReindexProgress),
+    ) -> Result<String, DistantError> {
+        self.reindex_with_alias_with_options(alias, index_body, source, on_progress, &RequestOptions::default()).await
+    }
+
+    // the options bound and cancel the copy or ingest, a cancelled reindex leaves the alias untouched
+    pub async fn reindex_with_alias_with_options(&self,
+                                                 alias: &str,
+                                                 index_body: Value,
+                                                 source: ReindexSource,
+                                                 mut on_progress: impl FnMut(ReindexProgress),
+                                                 options: &RequestOptions,
+    ) -> Result<String, DistantError> {
+        let current_indices = self.get_alias_indices(alias).await?;
+        if current_indices.len() > 1 {
+            return Err(DistantError::GeneralError(format!("Alias {} points to several indices: {:?}", alias, current_indices)));
         }
+        let current_index = current_indices.into_iter().next();
+        let concrete_index = current_index.is_none() && self.index_exists(alias).await?;
+        if concrete_index {
+            self.check_removable(alias).map_err(DistantError::UnsafeOperation)?;
+        }
+        let next_version = current_index.as_deref().and_then(versioned_index_number).unwrap_or(0) + 1;
+        let new_index = format!("{}_v{}", alias, next_version);
+
+        self.create_index(&new_index, index_body).await?;
+        on_progress(ReindexProgress::CreatedIndex(new_index.clone()));
+
+        let source_index = if concrete_index { Some(alias.to_string()) } else { current_index.clone() };
+        if let Err(e) = self.fill_and_swap(alias, &new_index, source_index.as_deref(), concrete_index, source, &mut on_progress, options).await {
+            // an orphaned version would make every retry fail with resource_already_exists
+            if let Err(reason) = self.delete_index(&self.scoped_index(&new_index)).await {
+                warn!("Failed to remove {} after the reindex failed: {}", new_index, reason);
+            }
+            return Err(e);
+        }
+        if concrete_index {
+            on_progress(ReindexProgress::RemovedIndex(alias.to_string()));
+        }
+
+        // the new index is live once the alias moved, failing to remove the old one only leaves it behind
+        if let Some(current_index) = current_index {
+            let removed = match self.check_removable(&current_index) {
+                Ok(()) => self.delete_index(&self.scoped_index(&current_index)).await,
+                Err(reason) => Err(reason),
+            };
+            match removed {
+                Ok(()) => on_progress(ReindexProgress::RemovedIndex(current_index)),
+                Err(reason) => {
+                    warn!("Failed to remove {} after moving the alias {} to {}: {}", current_index, alias, new_index, reason);
+                    on_progress(ReindexProgress::RemoveFailed { index: current_index, reason });
+                }
+            }
+        }
+        Ok(new_index)
+    }
+
+    // fill the new index and point the alias at it once it holds every document
+    async fn fill_and_swap(&self,
+                           alias: &str,
+                           new_index: &str,
+                           source_index: Option<&str>,
+                           concrete_index: bool,
+                           source: ReindexSource,
+                           on_progress: &mut impl FnMut(ReindexProgress),
+                           options: &RequestOptions,
+    ) -> Result<(), DistantError> {
+        let expected_count = match source {
+            ReindexSource::CurrentIndex => {
+                let source_index = source_index.ok_or_else(|| {
+                    DistantError::GeneralError(format!("Alias {} has no index to copy from", alias))
+                })?;
+                self.copy_index(source_index, new_index, on_progress, options).await?;
+                self.document_count(source_index).await?
+            }
+            ReindexSource::Entries(entries) => {
+                // entries sharing an id end up as a single document
+                let expected_count = entries.iter().map(|entry| entry.unique_id.as_str()).collect::<HashSet<&str>>().len() as u64;
+                let count = entries.len();
+                self.index_with_options(new_index, entries, options).await?;
+                self.refresh_index(new_index).await?;
+                on_progress(ReindexProgress::Ingested(count));
+                expected_count
+            }
+        };
+        let count = self.document_count(new_index).await?;
+        if count < expected_count {
+            return Err(DistantError::GeneralError(format!("{} holds {} of {} documents, the alias {} was not moved", new_index, count, expected_count, alias)));
+        }
+
+        match source_index {
+            Some(source_index) if concrete_index => self.replace_index_with_alias(source_index, alias, new_index).await?,
+            Some(source_index) => self.swap_alias(alias, source_index, new_index).await?,
+            None => self.create_alias(new_index, alias).await?,
+        }
+        on_progress(ReindexProgress::SwappedAlias { alias: alias.to_string(), index: new_index.to_string() });
         Ok(())
     }
 
+    // atomically remove the index and add an alias with its name pointing to another index
+    async fn replace_index_with_alias(&self, index_name: &str, alias: &str, to_index: &str) -> Result<(), DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let alias = self.scoped_index(alias);
+        let to_index = self.scoped_index(to_index);
+        let result = self.client
+            .indices()
+            .update_aliases()
+            .body(json!({
+                "actions": [
+                    { "add": { "index": to_index, "alias": alias } },
+                    { "remove_index": { "index": index_name } }
+                ]
+            }))
+            .send().await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(()),
+            _ => {
+                Err(DistantError::from_response(result).await)
+            }
+        }
+    }
+
+    async fn index_exists(&self, index_name: &str) -> Result<bool, DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let result = self.client
+            .indices()
+            .exists(IndicesExistsParts::Index(&[index_name.as_str()]))
+            .send().await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            _ => Err(DistantError::from_response(result).await),
+        }
+    }
+
+    async fn refresh_index(&self, index_name: &str) -> Result<(), DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let result = self.client
+            .indices()
+            .refresh(IndicesRefreshParts::Index(&[index_name.as_str()]))
+            .send().await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(()),
+            _ => Err(DistantError::from_response(result).await),
+        }
+    }
+
+    // number of documents in the index, without a query
+    async fn document_count(&self, index_name: &str) -> Result<u64, DistantError> {
+        let index_name = self.scoped_index(index_name);
+        let result = self.client
+            .count(CountParts::Index(&[index_name.as_str()]))
+            .send().await?;
+        match result.status_code() {
+            StatusCode::OK => Ok(result.json::<CountResult>().await?.count),
+            _ => Err(DistantError::from_response(result).await),
+        }
+    }
+
+    async fn copy_index(&self, from_index: &str, to_index: &str, on_progress: &mut impl FnMut(ReindexProgress), options: &RequestOptions) -> Result<(), DistantError> {
+        let from_index = self.scoped_index(from_index);
+        let to_index = self.scoped_index(to_index);
+        let result = self.client
+            .reindex()
+            .refresh(true)
+            .wait_for_completion(false)
+            .body(json!({
+                "source": { "index": from_index },
+                "dest": { "index": to_index }
+            }))
+            .send().await?;
+        if result.status_code() != StatusCode::OK {
+            return Err(DistantError::from_response(result).await);
+        }
+        let submitted = result.json::<TaskSubmitted>().await?;
+        let response = self.wait_for_task_with_progress(&submitted.task, options, |status| {
+            if let Some(task_status) = status.task.as_ref().and_then(|task| task.get("status")) {
+                on_progress(ReindexProgress::Copying {
+                    created: task_status["created"].as_i64().unwrap_or(0),
+                    total: task_status["total"].as_i64().unwrap_or(0),
+                });
+            }
+        }).await?;
+        match response["failures"].as_array() {
+            Some(failures) if !failures.is_empty() => {
+                Err(DistantError::GeneralError(format!("Reindexing {} into {} failed: {:?}", from_index, to_index, failures)))
+            }
+            _ => Ok(())
+        }
+    }
+
     // scroll
-    pub async fn scroll(&self, scroll_id: &str) -> Result<DistantElasticSearchResult, Error> {
-        let scroll = self.client.scroll(ScrollParts::ScrollId(scroll_id))
-            .body(
-                json! {
-                    {
-                        "scroll": "5m",
+    pub async fn scroll(&self, scroll_id: &str) -> Result<DistantElasticSearchResult, DistantError> {
+        self.scroll_with_options(scroll_id, &RequestOptions::default()).await
+    }
+
+    pub async fn scroll_with_options(&self, scroll_id: &str, options: &RequestOptions) -> Result<DistantElasticSearchResult, DistantError> {
+        // a scroll request that failed after reaching the cluster may already have advanced the cursor
+        let scroll = self.with_options(options, self.send_with_retry("scroll", false, || {
+            self.client.scroll(ScrollParts::ScrollId(scroll_id))
+                .body(
+                    json! {
+                        {
+                            "scroll": "5m",
+                        }
                     }
-                }
-            ).send().await;
-        match scroll {
-            Ok(scroll) => {
-                let parsed_value = scroll.json::<DistantElasticSearchResult>().await.expect("Cannot unwrap scroll response");
+                ).send()
+        })).await?;
+        match scroll.status_code() {
+            StatusCode::OK => {
+                let parsed_value = scroll.json::<DistantElasticSearchResult>().await?;
                 Ok(parsed_value)
             }
-            Err(e) => {
-                println!("{:?}", e);
-                Err(e)
+            _ => {
+                Err(DistantError::from_response(scroll).await)
             }
         }
     }
 
-    // list all indices in the elasticsearch
+    // list all indices in the elasticsearch, or only those of the namespace with the namespace prefix stripped
     pub async fn list_indices(&self) -> Result<Vec<IndexInfo>, Error> {
+        let pattern = self.scoped_index("*");
+        let patterns = [pattern.as_str()];
+        let index_parts = match self.namespace {
+            Some(_) => CatIndicesParts::Index(&patterns),
+            None => CatIndicesParts::None,
+        };
         let indices = self.client
             .cat()
-            .indices(CatIndicesParts::None)
+            .indices(index_parts)
             .format("json")
+            .bytes(Bytes::B)
             .send()
             .await;
         match indices {
             Ok(indices) => {
-                let response_body = indices.json::<Vec<IndexInfo>>().await;
-                response_body
+                let response_body = indices.json::<Vec<IndexInfo>>().await?;
+                Ok(response_body
+                    .into_iter()
+                    .filter_map(|mut index| {
+                        let name = self.unscoped_index(&index.index)?.to_string();
+                        index.index = name;
+                        Some(index)
+                    })
+                    .collect())
             }
             Err(e) => {
                 println!("{:?}", e);
@@ -243,6 +1627,33 @@ impl DistantClient {
         }
     }
 
+    // statistics and field mappings of a single index
+    pub async fn index_stats(&self, index_name: &str) -> Result<IndexStats, DistantError> {
+        let scoped_name = self.scoped_index(index_name);
+        let stats = self.client
+            .indices()
+            .stats(IndicesStatsParts::Index(&[scoped_name.as_str()]))
+            .send().await?;
+        if stats.status_code() != StatusCode::OK {
+            return Err(DistantError::from_response(stats).await);
+        }
+        // both responses are keyed by the concrete index, which differs from the name of an alias
+        let stats = stats.json::<IndicesStatsResponse>().await?;
+        if stats.indices.len() > 1 {
+            return Err(DistantError::GeneralError(format!("{} points to several indices", index_name)));
+        }
+        let entry = stats.indices.into_iter()
+            .next()
+            .map(|(_, entry)| entry)
+            .ok_or_else(|| DistantError::IndexNotFound(index_name.to_string()))?;
+        let mapping = self.client
+            .indices()
+            .get_mapping(IndicesGetMappingParts::Index(&[scoped_name.as_str()]))
+            .send().await?;
+        let mapping = self.single_index_entry(index_name, mapping).await?;
+        Ok(IndexStats::new(index_name.to_string(), &entry.total, &mapping))
+    }
+
     // check if file name exists in the elasticsearch
     pub async fn check_if_exist(&self, index: Vec<&str>, file_name: &str) -> Result<bool, Error> {
         let exists = self.search_by_filename(index, file_name, 0).await;
@@ -259,6 +1670,8 @@ impl DistantClient {
     }
 
     async fn search_by_filename(&self, index: Vec<&str>, file_name: &str, size: i64) -> Result<Value, Error> {
+        let index: Vec<String> = index.iter().map(|index_name| self.scoped_index(index_name)).collect();
+        let index: Vec<&str> = index.iter().map(String::as_str).collect();
         let exists = self.client
             .search(SearchParts::Index(&index[..]))
             .scroll("1d")
@@ -296,6 +1709,24 @@ impl DistantClient {
     }
 }
 
+// the searched name a hit from the index came from: the index itself, or the alias "<name>"
+// of a versioned index "<name>_v<n>", any other index behind an alias is reported as it is
+fn library_name<'a>(index_name: &'a str, index_names: &'a [String]) -> &'a str {
+    if index_names.iter().any(|name| name == index_name) {
+        return index_name;
+    }
+    match index_name.rsplit_once("_v") {
+        Some((alias, _)) if versioned_index_number(index_name).is_some() && index_names.iter().any(|name| name == alias) => alias,
+        _ => index_name,
+    }
+}
+
+// version number of an index named "<alias>_v<n>"
+fn versioned_index_number(index_name: &str) -> Option<u32> {
+    let (_, version) = index_name.rsplit_once("_v")?;
+    version.parse().ok()
+}
+
 // tests
 #[cfg(test)]
 mod test {
@@ -314,11 +1745,19 @@ mod test {
         println!("{:?}", result);
     }
 
+    #[tokio::test]
+    async fn test_wait_for_status() {
+        let distant_client = DistantClient::new();
+        let result = distant_client.wait_for_status(HealthStatus::Yellow, std::time::Duration::from_secs(10)).await.unwrap();
+        assert_ne!(result.status, HealthStatus::Red);
+    }
+
 
     #[tokio::test]
     async fn remove_all_indices() {
-        let distant_client = DistantClient::new();
-        let result = distant_client.remove_all_indices().await;
+        let distant_client = DistantClient::new().with_namespace("test").unwrap();
+        let result = distant_client.remove_all_indices(REMOVE_ALL_INDICES_CONFIRMATION).await.unwrap();
+        assert!(result.failed.is_empty());
 
         // sleep for 2 seconds
         tokio::time::sleep(std::time::Duration::from_secs(2)).await;
@@ -326,6 +1765,155 @@ mod test {
         assert_eq!(all_indices.len(), 0);
     }
 
+    #[test]
+    fn test_check_removable() {
+        let distant_client = DistantClient::new().with_namespace("test").unwrap();
+        assert!(distant_client.check_removable("index").is_ok());
+        assert!(distant_client.check_removable("*").is_err());
+        assert!(distant_client.check_removable("a,b").is_err());
+        assert!(distant_client.check_removable("_all").is_err());
+        assert!(DistantClient::new().check_removable(".kibana").is_err());
+    }
+
+    #[test]
+    fn test_versioned_index_number() {
+        assert_eq!(versioned_index_number("library_v3"), Some(3));
+        assert_eq!(versioned_index_number("library_v12"), Some(12));
+        assert_eq!(versioned_index_number("library"), None);
+        assert_eq!(versioned_index_number("library_vx"), None);
+    }
+
+    fn msearch_requests() -> Vec<MultiSearchRequest> {
+        let query = SearchQuery { length: 10, ..SearchQuery::default() };
+        vec![
+            MultiSearchRequest::new("library", query.clone()),
+            MultiSearchRequest::new("library", query.clone())
+                .with_search_options(SearchOptions::default().with_relevance_profile("missing")),
+            MultiSearchRequest::new("notes", query),
+        ]
+    }
+
+    #[test]
+    fn test_msearch_body_skips_invalid_requests() {
+        let distant_client = DistantClient::new().with_namespace("test").unwrap();
+        let (body, errors) = distant_client.msearch_body(&msearch_requests());
+        assert_eq!(body.len(), 4);
+        assert_eq!(body[0], json!({ "index": ["test_library"] }));
+        assert_eq!(body[2], json!({ "index": ["test_notes"] }));
+        assert_eq!(body[3]["size"], 10);
+        assert!(errors[0].is_none());
+        assert!(matches!(errors[1], Some(DistantError::GeneralError(_))));
+        assert!(errors[2].is_none());
+    }
+
+    #[test]
+    fn test_msearch_mixed_results() {
+        let distant_client = DistantClient::new().with_namespace("test").unwrap();
+        let requests = msearch_requests();
+        let (_, errors) = distant_client.msearch_body(&requests);
+        let responses: Value = serde_json::from_str(r#"[
+            {"took":1,"timed_out":false,"hits":{"total":{"value":1,"relation":"eq"},"max_score":1.0,"hits":[
+                {"_id":"a","_index":"test_library","_score":1.0,"_type":"pdf","_source":{"uniqueId":"a","text":"apple"}}]},"status":200},
+            {"error":{"root_cause":[{"type":"index_not_found_exception","reason":"no such index [test_notes]","index":"test_notes"}],
+                "type":"index_not_found_exception","reason":"no such index [test_notes]","index":"test_notes"},"status":404}]"#).unwrap();
+        let results = distant_client.msearch_results(&requests, errors, responses.as_array().unwrap().clone());
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().hits.hits[0].index, "library");
+        assert!(matches!(results[1], Err(DistantError::GeneralError(_))));
+        assert!(matches!(&results[2], Err(DistantError::IndexNotFound(index)) if index == "test_notes"));
+    }
+
+    #[test]
+    fn test_more_like_this_body() {
+        let distant_client = DistantClient::new().with_namespace("test").unwrap();
+        let source = MoreLikeThisSource::Document("a".to_string());
+        let body = distant_client.more_like_this_body(&distant_client.scoped_index("library"), &source, &MoreLikeThisOptions::default());
+        assert_eq!(body["size"], 10);
+        assert_eq!(body["query"]["more_like_this"]["like"], json!([{ "_index": "test_library", "_id": "a" }]));
+        assert_eq!(body["query"]["more_like_this"]["include"], false);
+        assert!(body.get("highlight").is_some());
+    }
+
+    #[test]
+    fn test_more_like_this_response() {
+        let distant_client = DistantClient::new().with_namespace("test").unwrap();
+        let json = r#"{"took":4,"timed_out":false,"hits":{"total":{"value":2,"relation":"eq"},"max_score":2.5,"hits":[
+            {"_id":"b","_index":"test_library_v3","_score":2.5,"_type":"pdf","_source":{"uniqueId":"b","text":"apple pie"}},
+            {"_id":"c","_index":"test_library_v3","_score":1.2,"_type":"pdf","_source":{"uniqueId":"c","text":"apple tart"}}]}}"#;
+        let mut result: DistantElasticSearchResult = serde_json::from_str(json).unwrap();
+        distant_client.complete_search_result(&mut result, &["library".to_string()], &distant_client.search_options);
+        assert_eq!(result.hit_libraries(), vec!["library", "library"]);
+        let response: CarrelSearchResponse = result.into();
+        let ids: Vec<&str> = response.results.iter().map(|result| result.result.as_ref().unwrap().unique_id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_library_name() {
+        let searched = vec!["library".to_string(), "notes".to_string()];
+        assert_eq!(library_name("notes", &searched), "notes");
+        assert_eq!(library_name("library_v2", &searched), "library");
+        assert_eq!(library_name("other_v2", &searched), "other_v2");
+        assert_eq!(library_name("library_v2", &["library".to_string()]), "library");
+    }
+
+    #[test]
+    fn test_library_name_alias_over_several_indices() {
+        let searched = vec!["library".to_string()];
+        assert_eq!(library_name("papers", &searched), "papers");
+        assert_eq!(library_name("notes_v3", &searched), "notes_v3");
+    }
+
+    #[test]
+    fn test_complete_search_result_reports_alias() {
+        let distant_client = DistantClient::new().with_namespace("test").unwrap();
+        let json = r#"{"took":1,"timed_out":false,"hits":{"total":{"value":1,"relation":"eq"},"max_score":1.0,"hits":[
+            {"_id":"a","_index":"test_library_v2","_score":1.0,"_type":"pdf","_source":{"uniqueId":"a","text":"apple"}}]}}"#;
+        let mut result: DistantElasticSearchResult = serde_json::from_str(json).unwrap();
+        distant_client.complete_search_result(&mut result, &["library".to_string()], &SearchOptions::default());
+        assert_eq!(result.hits.hits[0].index, "library");
+    }
+
+    #[test]
+    fn test_namespace_index_names() {
+        let distant_client = DistantClient::new().with_namespace("alice").unwrap();
+        assert_eq!(distant_client.scoped_index("library"), "alice_library");
+        assert_eq!(distant_client.unscoped_index("alice_library"), Some("library"));
+        assert_eq!(distant_client.unscoped_index("bob_library"), None);
+        assert_eq!(distant_client.unscoped_index("alicelibrary"), None);
+        assert_eq!(DistantClient::new().unscoped_index("library"), Some("library"));
+    }
+
+    #[test]
+    fn test_prefix_namespaces_are_separate() {
+        let alice = DistantClient::new().with_namespace("alice").unwrap();
+        let alicebob = DistantClient::new().with_namespace("alicebob").unwrap();
+        assert_eq!(alice.unscoped_index(&alicebob.scoped_index("library")), None);
+        assert_eq!(alicebob.unscoped_index(&alice.scoped_index("library")), None);
+        assert_eq!(alice.unscoped_index(&alice.scoped_index("bob_library")), Some("bob_library"));
+        assert!(DistantClient::new().with_namespace("alice_bob").is_err());
+        assert!(DistantClient::new().with_namespace("").is_err());
+        assert!(DistantClient::new().with_namespace(".kibana").is_err());
+        assert!(DistantClient::new().with_namespace("a*").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_remove_all_indices_requires_confirmation() {
+        let distant_client = DistantClient::new().with_namespace("test").unwrap();
+        assert!(distant_client.remove_all_indices("yes").await.is_err());
+        assert!(DistantClient::new().remove_all_indices(REMOVE_ALL_INDICES_CONFIRMATION).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_cancelled() {
+        let distant_client = DistantClient::new();
+        let token = tokio_util::sync::CancellationToken::new();
+        token.cancel();
+        let options = RequestOptions::default().with_cancellation_token(token);
+        let result = distant_client.search_with_options("test_index".to_string(), SearchQuery::default(), &SearchOptions::default(), &options).await;
+        assert!(matches!(result, Err(DistantError::Cancelled)));
+    }
+
     #[tokio::test]
     async fn test_index_function() -> Result<(), Box<dyn std::error::Error>> {
         // Create a DistantClient instance
@@ -0,0 +1,104 @@
+use carrel_commons::carrel::shared::search::v1::CarrelSearchResultItem;
+use futures::future::BoxFuture;
+use crate::errors::DistantError;
+
+/// Turns texts into dense vectors for semantic search, e.g. a local model or a remote embedding API.
+pub trait EmbeddingProvider: Send + Sync {
+    /// Length of every vector returned by `embed`.
+    fn dimensions(&self) -> usize;
+
+    /// One vector per text, in the same order.
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>, DistantError>>;
+}
+
+/// The text of a passage that is embedded: its title followed by its text.
+pub fn embedding_text(item: &CarrelSearchResultItem) -> String {
+    if item.title.is_empty() {
+        item.text.clone()
+    } else {
+        format!("{}\n{}", item.title, item.text)
+    }
+}
+
+/// Whether the vector can be compared by cosine similarity: the similarity with a zero vector is NaN.
+pub fn is_usable_embedding(vector: &[f32]) -> bool {
+    vector.iter().any(|value| *value != 0.0)
+}
+
+/// Deterministic bag-of-words embeddings using feature hashing, for tests and offline use.
+/// Texts sharing words get similar vectors, but there is no notion of meaning.
+pub struct HashingEmbeddingProvider {
+    dimensions: usize,
+}
+
+impl HashingEmbeddingProvider {
+    pub fn new(dimensions: usize) -> Self {
+        HashingEmbeddingProvider { dimensions }
+    }
+
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0f32; self.dimensions];
+        for word in text.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()) {
+            let hash = fnv1a(word.to_lowercase().as_bytes());
+            let position = (hash % self.dimensions as u64) as usize;
+            // the top bit decides the sign so that collisions cancel out on average
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[position] += sign;
+        }
+        let norm = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|value| *value /= norm);
+        }
+        vector
+    }
+}
+
+impl EmbeddingProvider for HashingEmbeddingProvider {
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>, DistantError>> {
+        Box::pin(async move {
+            Ok(texts.iter().map(|text| self.embed_text(text)).collect())
+        })
+    }
+}
+
+// stable across runs and platforms, unlike the standard library hasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_usable_embedding, EmbeddingProvider, HashingEmbeddingProvider};
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[tokio::test]
+    async fn test_hashing_embeddings() {
+        let provider = HashingEmbeddingProvider::new(64);
+        let texts = vec!["apple pie recipe".to_string(), "Apple pie".to_string(), "quantum field theory".to_string()];
+        let vectors = provider.embed(&texts).await.unwrap();
+        assert_eq!(vectors.len(), 3);
+        assert!(vectors.iter().all(|vector| vector.len() == 64));
+        assert!(cosine(&vectors[0], &vectors[1]) > cosine(&vectors[0], &vectors[2]));
+        assert_eq!(vectors[0], provider.embed_text("apple pie recipe"));
+    }
+
+    #[test]
+    fn test_empty_text_is_not_usable() {
+        let provider = HashingEmbeddingProvider::new(8);
+        assert!(!is_usable_embedding(&provider.embed_text("")));
+        assert!(!is_usable_embedding(&provider.embed_text(" -- ")));
+        assert!(is_usable_embedding(&provider.embed_text("apple")));
+    }
+}
@@ -1,11 +1,168 @@
 use elasticsearch::Error;
+use elasticsearch::http::response::Response;
 use thiserror::Error;
+use crate::responses::bulk_result::BulkItemFailure;
+use crate::responses::error_response::ErrorResponse;
 
 #[derive(Error, Debug)]
 pub enum DistantError {
     #[error("Elasticsearch error: {0}")]
     ElasticsearchError(#[from] Error),
 
+    #[error("Index not found: {0}")]
+    IndexNotFound(String),
+
+    #[error("Document not found: {0}")]
+    DocumentNotFound(String),
+
+    #[error("Mapping conflict: {0}")]
+    MappingConflict(String),
+
+    #[error("Query parse error: {reason} (line {line:?}, column {col:?})")]
+    QueryParseError {
+        reason: String,
+        line: Option<u32>,
+        col: Option<u32>,
+    },
+
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    #[error("Timeout: {0}")]
+    Timeout(String),
+
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+
+    #[error("Elasticsearch responded with {status} {error_type}: {reason}")]
+    ResponseError {
+        status: u16,
+        error_type: String,
+        reason: String,
+    },
+
+    #[error("{} bulk items failed, first: {:?}", .0.len(), .0.first())]
+    BulkItemsFailed(Vec<BulkItemFailure>),
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    #[error("Refused unsafe operation: {0}")]
+    UnsafeOperation(String),
+
     #[error("General error: {0}")]
     GeneralError(String),
-}
\ No newline at end of file
+}
+
+impl DistantError {
+    // build the error from a response with a non-success status code
+    pub async fn from_response(response: Response) -> Self {
+        let status = response.status_code().as_u16();
+        match response.text().await {
+            Ok(body) => DistantError::from_error_body(status, &body),
+            Err(e) => DistantError::ElasticsearchError(e),
+        }
+    }
+
+    pub fn from_error_body(status: u16, body: &str) -> Self {
+        let error_response = match serde_json::from_str::<ErrorResponse>(body) {
+            Ok(error_response) => error_response,
+            Err(_) => {
+                return DistantError::from_status(status, String::new(), body.to_string());
+            }
+        };
+        let cause = error_response.error.innermost();
+        let reason = cause.reason.clone()
+            .or_else(|| error_response.error.reason.clone())
+            .unwrap_or_default();
+        match cause.error_type.as_str() {
+            "index_not_found_exception" => {
+                DistantError::IndexNotFound(cause.index.clone().unwrap_or(reason))
+            }
+            "document_missing_exception" => DistantError::DocumentNotFound(reason),
+            "mapper_parsing_exception" | "strict_dynamic_mapping_exception" | "mapper_exception" => {
+                DistantError::MappingConflict(reason)
+            }
+            "illegal_argument_exception" if reason.contains("mapper [") => {
+                DistantError::MappingConflict(reason)
+            }
+            "parsing_exception" | "x_content_parse_exception" | "query_shard_exception" => {
+                DistantError::QueryParseError { reason, line: cause.line, col: cause.col }
+            }
+            "es_rejected_execution_exception" | "circuit_breaking_exception" => {
+                DistantError::RateLimited(reason)
+            }
+            "security_exception" => DistantError::Unauthorized(reason),
+            "timeout_exception" | "receive_timeout_transport_exception" => DistantError::Timeout(reason),
+            error_type => DistantError::from_status(status, error_type.to_string(), reason),
+        }
+    }
+
+    // a 404 alone does not say what is missing (index, document, task), it stays a ResponseError
+    fn from_status(status: u16, error_type: String, reason: String) -> Self {
+        match status {
+            401 | 403 => DistantError::Unauthorized(reason),
+            408 | 504 => DistantError::Timeout(reason),
+            429 => DistantError::RateLimited(reason),
+            _ => DistantError::ResponseError { status, error_type, reason },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DistantError;
+
+    #[test]
+    fn test_index_not_found() {
+        let body = r#"{"error":{"root_cause":[{"type":"index_not_found_exception","reason":"no such index [library]","index_uuid":"_na_","resource.type":"index_or_alias","resource.id":"library","index":"library"}],"type":"index_not_found_exception","reason":"no such index [library]","index":"library"},"status":404}"#;
+        match DistantError::from_error_body(404, body) {
+            DistantError::IndexNotFound(index) => assert_eq!(index, "library"),
+            error => panic!("unexpected error {:?}", error),
+        }
+    }
+
+    #[test]
+    fn test_query_parse_error() {
+        let body = r#"{"error":{"root_cause":[{"type":"parsing_exception","reason":"unknown query [multi_matc]","line":1,"col":34}],"type":"parsing_exception","reason":"unknown query [multi_matc]","line":1,"col":34,"caused_by":{"type":"named_object_not_found_exception","reason":"[1:34] unknown field [multi_matc]"}},"status":400}"#;
+        match DistantError::from_error_body(400, body) {
+            DistantError::QueryParseError { reason, line, col } => {
+                assert_eq!(reason, "unknown query [multi_matc]");
+                assert_eq!(line, Some(1));
+                assert_eq!(col, Some(34));
+            }
+            error => panic!("unexpected error {:?}", error),
+        }
+    }
+
+    #[test]
+    fn test_document_not_found() {
+        let body = r#"{"error":{"root_cause":[{"type":"document_missing_exception","reason":"[_doc][a]: document missing","index_uuid":"x","shard":"0","index":"library"}],"type":"document_missing_exception","reason":"[_doc][a]: document missing","index_uuid":"x","shard":"0","index":"library"},"status":404}"#;
+        assert!(matches!(DistantError::from_error_body(404, body), DistantError::DocumentNotFound(_)));
+    }
+
+    #[test]
+    fn test_other_not_found() {
+        let body = r#"{"error":{"root_cause":[{"type":"resource_not_found_exception","reason":"task [abc:1] isn't running and hasn't stored its results"}],"type":"resource_not_found_exception","reason":"task [abc:1] isn't running and hasn't stored its results"},"status":404}"#;
+        match DistantError::from_error_body(404, body) {
+            DistantError::ResponseError { status, error_type, .. } => {
+                assert_eq!(status, 404);
+                assert_eq!(error_type, "resource_not_found_exception");
+            }
+            error => panic!("unexpected error {:?}", error),
+        }
+        assert!(matches!(DistantError::from_error_body(404, ""), DistantError::ResponseError { status: 404, .. }));
+    }
+
+    #[test]
+    fn test_status_fallback() {
+        assert!(matches!(DistantError::from_error_body(429, "Too Many Requests"), DistantError::RateLimited(_)));
+        assert!(matches!(DistantError::from_error_body(401, ""), DistantError::Unauthorized(_)));
+    }
+}
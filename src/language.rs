@@ -0,0 +1,145 @@
+use carrel_commons::carrel::shared::search::v1::CarrelSearchResultItem;
+
+/// Languages with their own analyzed copy of the text fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    English,
+    German,
+    Chinese,
+}
+
+/// Fields of CarrelSearchResultItem copied into a subfield for their detected language.
+pub const LANGUAGE_FIELDS: [&str; 3] = ["title", "text", "context"];
+
+impl Language {
+    // suffix of the language subfields, e.g. text_en
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::Chinese => "zh",
+        }
+    }
+
+    // built-in analyzer, cjk for Chinese as smartcn needs the analysis-smartcn plugin
+    pub fn default_analyzer(self) -> &'static str {
+        match self {
+            Language::English => "english",
+            Language::German => "german",
+            Language::Chinese => "cjk",
+        }
+    }
+}
+
+/// The languages analyzed in an index and the analyzer used for each.
+#[derive(Debug, Clone)]
+pub struct LanguageAnalysis {
+    pub analyzers: Vec<(Language, String)>,
+}
+
+impl Default for LanguageAnalysis {
+    fn default() -> Self {
+        LanguageAnalysis {
+            analyzers: [Language::English, Language::German, Language::Chinese]
+                .iter()
+                .map(|language| (*language, language.default_analyzer().to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl LanguageAnalysis {
+    // use another analyzer for the language, e.g. smartcn for Chinese when the plugin is installed
+    pub fn with_analyzer(mut self, language: Language, analyzer: &str) -> Self {
+        self.analyzers.retain(|(existing, _)| *existing != language);
+        self.analyzers.push((language, analyzer.to_string()));
+        self
+    }
+
+    pub fn languages(&self) -> Vec<Language> {
+        self.analyzers.iter().map(|(language, _)| *language).collect()
+    }
+
+    // the language of the item among the analyzed ones, detected from its text or else its title
+    pub fn detect(&self, item: &CarrelSearchResultItem) -> Option<Language> {
+        let text = if item.text.trim().is_empty() { &item.title } else { &item.text };
+        detect_language(text).filter(|language| self.languages().contains(language))
+    }
+}
+
+/// The subfield holding the text of the field in the language.
+pub fn language_field(field: &str, language: Language) -> String {
+    format!("{}_{}", field, language.code())
+}
+
+/// The query fields followed by the language subfields of those that have them, keeping the boosts.
+pub fn expand_language_fields(fields: &[String], languages: &[Language]) -> Vec<String> {
+    let mut expanded = fields.to_vec();
+    for field in fields {
+        let (name, boost) = match field.split_once('^') {
+            Some((name, boost)) => (name, format!("^{}", boost)),
+            None => (field.as_str(), String::new()),
+        };
+        if LANGUAGE_FIELDS.contains(&name) {
+            expanded.extend(languages.iter().map(|language| format!("{}{}", language_field(name, *language), boost)));
+        }
+    }
+    expanded
+}
+
+const ENGLISH_STOPWORDS: [&str; 12] = ["the", "and", "of", "to", "is", "in", "that", "with", "for", "this", "are", "was"];
+const GERMAN_STOPWORDS: [&str; 12] = ["der", "die", "das", "und", "ist", "nicht", "mit", "ein", "eine", "zu", "den", "von"];
+
+/// Guess the language of a text from its script and common words, None when there is no clear signal.
+pub fn detect_language(text: &str) -> Option<Language> {
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+    if letters == 0 {
+        return None;
+    }
+    let han = text.chars().filter(|c| is_han(*c)).count();
+    if han * 5 >= letters {
+        return Some(Language::Chinese);
+    }
+    let mut english = 0;
+    let mut german = 0;
+    for word in text.split(|c: char| !c.is_alphabetic()).filter(|word| !word.is_empty()) {
+        let word = word.to_lowercase();
+        if ENGLISH_STOPWORDS.contains(&word.as_str()) {
+            english += 1;
+        }
+        if GERMAN_STOPWORDS.contains(&word.as_str()) || word.contains(['ä', 'ö', 'ü', 'ß']) {
+            german += 1;
+        }
+    }
+    if german > english {
+        Some(Language::German)
+    } else if english > 0 {
+        Some(Language::English)
+    } else {
+        None
+    }
+}
+
+fn is_han(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+#[cfg(test)]
+mod test {
+    use super::{detect_language, expand_language_fields, Language};
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("The history of the printing press"), Some(Language::English));
+        assert_eq!(detect_language("Die Geschichte der Buchdruckerkunst und ihre Folgen"), Some(Language::German));
+        assert_eq!(detect_language("印刷术的历史 and Gutenberg"), Some(Language::Chinese));
+        assert_eq!(detect_language("Gutenberg 1450"), None);
+    }
+
+    #[test]
+    fn test_expand_language_fields() {
+        let fields = vec!["title^3".to_string(), "sourceName".to_string()];
+        assert_eq!(expand_language_fields(&fields, &[Language::English, Language::Chinese]),
+                   vec!["title^3", "sourceName", "title_en^3", "title_zh^3"]);
+    }
+}
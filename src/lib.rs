@@ -2,6 +2,13 @@ pub mod distant_client;
 pub mod responses;
 pub mod util;
 pub mod errors;
+pub mod retry;
+pub mod request_options;
+pub mod mappings;
+pub mod query;
+pub mod embeddings;
+pub mod language;
+pub mod analysis;
 
 fn add(left: usize, right: usize) -> usize {
     left + right
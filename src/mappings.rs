@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+use carrel_commons::carrel::shared::search::v1::CarrelSearchResultItem;
+use serde_json::{json, Map, Value};
+use crate::analysis::{search_analyzer_properties, SearchAnalysis};
+use crate::language::{language_field, LanguageAnalysis, LANGUAGE_FIELDS};
+
+/// Name of the completion field populated from title, source name and tags.
+pub const SUGGEST_FIELD: &str = "suggest";
+
+/// Name of the dense_vector field populated by the EmbeddingProvider of the client.
+pub const EMBEDDING_FIELD: &str = "embedding";
+
+// text fields of CarrelSearchResultItem, mapped like the dynamic mapping would (text with a keyword subfield)
+const TEXT_FIELDS: [&str; 7] = ["title", "text", "context", "sourceName", "filePath", "uniqueId", "tags"];
+
+// index settings set by elasticsearch itself, an index cannot be created with them
+const GENERATED_SETTINGS: [&str; 6] = ["uuid", "creation_date", "provided_name", "version", "routing", "resize"];
+
+/// Index body (settings and mappings) for an index of CarrelSearchResultItem documents.
+pub fn carrel_index_body() -> Value {
+    let mut properties = Map::new();
+    for field in TEXT_FIELDS {
+        properties.insert(field.to_string(), json!({
+            "type": "text",
+            "fields": {
+                "keyword": { "type": "keyword", "ignore_above": 256 }
+            }
+        }));
+    }
+    properties.insert(SUGGEST_FIELD.to_string(), json!({
+        "type": "completion"
+    }));
+    json!({
+        "mappings": {
+            "properties": properties
+        }
+    })
+}
+
+/// Index body for CarrelSearchResultItem documents with an embedding field of the given dimensions.
+pub fn carrel_index_body_with_embeddings(dimensions: usize) -> Value {
+    let mut body = carrel_index_body();
+    body["mappings"]["properties"][EMBEDDING_FIELD] = json!({
+        "type": "dense_vector",
+        "dims": dimensions
+    });
+    body
+}
+
+/// Add the language subfields of the text fields, each analyzed with the analyzer of its language.
+pub fn add_language_fields(body: &mut Value, language_analysis: &LanguageAnalysis) {
+    for field in LANGUAGE_FIELDS {
+        for (language, analyzer) in &language_analysis.analyzers {
+            body["mappings"]["properties"][language_field(field, *language)] = json!({
+                "type": "text",
+                "analyzer": analyzer
+            });
+        }
+    }
+}
+
+/// Settings of an index, as returned by the get settings API, to create a copy of the index with.
+pub fn copyable_settings(settings: &Value) -> Value {
+    let mut index_settings = settings["index"].clone();
+    if let Value::Object(entries) = &mut index_settings {
+        for setting in GENERATED_SETTINGS {
+            entries.remove(setting);
+        }
+    }
+    index_settings
+}
+
+/// Add the synonyms and stopwords as the search analyzer of the text fields, keeping the other
+/// settings of the body and replacing only the filters and analyzer of the search analyzer.
+pub fn add_search_analysis(body: &mut Value, search_analysis: &SearchAnalysis) {
+    let settings = search_analysis.to_settings();
+    for section in ["filter", "analyzer"] {
+        if let Value::Object(definitions) = &settings["analysis"][section] {
+            for (name, definition) in definitions {
+                body["settings"]["analysis"][section][name] = definition.clone();
+            }
+        }
+    }
+    if let Value::Object(properties) = search_analyzer_properties() {
+        for (field, mapping) in properties {
+            body["mappings"]["properties"][field] = mapping;
+        }
+    }
+}
+
+/// Completion inputs for the item: its title, source name and tags.
+pub fn suggest_inputs(item: &CarrelSearchResultItem) -> Vec<String> {
+    let mut inputs: Vec<String> = vec![];
+    inputs.push(item.title.clone());
+    if let Some(source_name) = &item.source_name {
+        inputs.push(source_name.clone());
+    }
+    inputs.extend(item.tags.iter().cloned());
+    // keep the first occurrence of each input, in the original order
+    let mut seen = HashSet::new();
+    inputs.retain(|input| !input.trim().is_empty() && seen.insert(input.clone()));
+    inputs
+}
+
+#[cfg(test)]
+mod test {
+    use carrel_commons::carrel::shared::search::v1::CarrelSearchResultItem;
+    use serde_json::json;
+    use crate::analysis::{SearchAnalysis, SEARCH_ANALYZER};
+    use super::{add_search_analysis, copyable_settings, suggest_inputs};
+
+    #[test]
+    fn test_search_analysis_keeps_settings() {
+        let settings = json!({ "index": {
+            "number_of_shards": "3",
+            "number_of_replicas": "2",
+            "uuid": "abc",
+            "creation_date": "1700000000000",
+            "provided_name": "library_v1",
+            "version": { "created": "7140099" },
+            "analysis": {
+                "filter": { "carrel_synonyms": { "type": "synonym_graph", "synonyms": ["ml, machine learning"] } },
+                "analyzer": { "folding": { "type": "custom", "tokenizer": "standard", "filter": ["asciifolding"] } }
+            }
+        }});
+        let mut body = json!({ "settings": copyable_settings(&settings), "mappings": {} });
+        add_search_analysis(&mut body, &SearchAnalysis::default().with_synonyms(&["ai", "artificial intelligence"]));
+        assert_eq!(body["settings"]["number_of_shards"], "3");
+        assert_eq!(body["settings"]["number_of_replicas"], "2");
+        assert!(body["settings"].get("uuid").is_none());
+        assert!(body["settings"].get("version").is_none());
+        assert!(body["settings"]["analysis"]["analyzer"]["folding"].is_object());
+        assert!(body["settings"]["analysis"]["analyzer"][SEARCH_ANALYZER].is_object());
+        assert_eq!(body["settings"]["analysis"]["filter"]["carrel_synonyms"]["synonyms"], json!(["ai, artificial intelligence"]));
+    }
+
+    #[test]
+    fn test_suggest_inputs_dedup() {
+        let item = CarrelSearchResultItem {
+            title: "Printing".to_string(),
+            source_name: Some("Gutenberg".to_string()),
+            tags: vec!["history".to_string(), "Printing".to_string(), "".to_string(), "history".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(suggest_inputs(&item), vec!["Printing", "Gutenberg", "history"]);
+    }
+}
@@ -0,0 +1,105 @@
+use serde_json::{json, Map, Value};
+
+/// An aggregation requested alongside a search, returned as a facet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FacetRequest {
+    pub name: String,
+
+    pub kind: FacetKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FacetKind {
+    /// Most frequent values of the field.
+    Terms { field: String, size: usize },
+    /// Documents per calendar interval ("day", "month", "year"...) of a date field.
+    DateHistogram { field: String, calendar_interval: String },
+}
+
+const DEFAULT_FACET_SIZE: usize = 20;
+
+impl FacetRequest {
+    pub fn terms(name: &str, field: &str, size: usize) -> Self {
+        FacetRequest {
+            name: name.to_string(),
+            kind: FacetKind::Terms { field: field.to_string(), size },
+        }
+    }
+
+    pub fn date_histogram(name: &str, field: &str, calendar_interval: &str) -> Self {
+        FacetRequest {
+            name: name.to_string(),
+            kind: FacetKind::DateHistogram { field: field.to_string(), calendar_interval: calendar_interval.to_string() },
+        }
+    }
+
+    pub fn material_type() -> Self {
+        FacetRequest::terms("material_type", "materialType", DEFAULT_FACET_SIZE)
+    }
+
+    pub fn source_name() -> Self {
+        FacetRequest::terms("source_name", "sourceName.keyword", DEFAULT_FACET_SIZE)
+    }
+
+    pub fn tags() -> Self {
+        FacetRequest::terms("tags", "tags.keyword", DEFAULT_FACET_SIZE)
+    }
+
+    /// The data type of ElasticInputEntry, stored as the document type.
+    pub fn data_type() -> Self {
+        FacetRequest::terms("data_type", "_type", DEFAULT_FACET_SIZE)
+    }
+
+    pub fn file_path() -> Self {
+        FacetRequest::terms("file_path", "filePath.keyword", DEFAULT_FACET_SIZE)
+    }
+
+    /// The terms facets shown in the search sidebar.
+    pub fn carrel_defaults() -> Vec<FacetRequest> {
+        vec![
+            FacetRequest::material_type(),
+            FacetRequest::source_name(),
+            FacetRequest::tags(),
+            FacetRequest::data_type(),
+            FacetRequest::file_path(),
+        ]
+    }
+
+    pub fn aggregation(&self) -> Value {
+        match &self.kind {
+            FacetKind::Terms { field, size } => json!({
+                "terms": { "field": field, "size": size }
+            }),
+            FacetKind::DateHistogram { field, calendar_interval } => json!({
+                "date_histogram": { "field": field, "calendar_interval": calendar_interval, "min_doc_count": 1 }
+            }),
+        }
+    }
+}
+
+/// The "aggs" section of the search body for the facets.
+pub fn build_aggregations(facets: &[FacetRequest]) -> Value {
+    let mut aggregations = Map::new();
+    for facet in facets {
+        aggregations.insert(facet.name.clone(), facet.aggregation());
+    }
+    Value::Object(aggregations)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use super::{build_aggregations, FacetRequest};
+
+    #[test]
+    fn test_build_aggregations() {
+        let aggregations = build_aggregations(&[
+            FacetRequest::tags(),
+            FacetRequest::date_histogram("modified", "modified", "month"),
+        ]);
+        assert_eq!(aggregations, json!({
+            "tags": { "terms": { "field": "tags.keyword", "size": 20 } },
+            "modified": { "date_histogram": { "field": "modified", "calendar_interval": "month", "min_doc_count": 1 } }
+        }));
+    }
+}
@@ -0,0 +1,138 @@
+use serde_json::{json, Map, Value};
+use crate::responses::search_result::{OFFSET_MARKER_POST_TAG, OFFSET_MARKER_PRE_TAG};
+
+/// Highlighting requested with a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighlightOptions {
+    pub fields: Vec<HighlightField>,
+
+    pub pre_tag: String,
+
+    pub post_tag: String,
+
+    /// Only highlight fields the query matched on.
+    pub require_field_match: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighlightField {
+    pub name: String,
+
+    /// Characters per fragment, Elasticsearch defaults to 100.
+    pub fragment_size: Option<usize>,
+
+    /// Fragments returned per field, 0 returns the whole field highlighted.
+    pub number_of_fragments: Option<usize>,
+}
+
+impl HighlightField {
+    pub fn new(name: &str) -> Self {
+        HighlightField {
+            name: name.to_string(),
+            fragment_size: None,
+            number_of_fragments: None,
+        }
+    }
+
+    pub fn fragments(name: &str, fragment_size: usize, number_of_fragments: usize) -> Self {
+        HighlightField {
+            name: name.to_string(),
+            fragment_size: Some(fragment_size),
+            number_of_fragments: Some(number_of_fragments),
+        }
+    }
+
+    // highlight the whole value, for short fields such as titles
+    pub fn whole(name: &str) -> Self {
+        HighlightField {
+            name: name.to_string(),
+            fragment_size: None,
+            number_of_fragments: Some(0),
+        }
+    }
+}
+
+/// Every field highlighted with `<em>` tags, as search() always did.
+impl Default for HighlightOptions {
+    fn default() -> Self {
+        HighlightOptions {
+            fields: vec![HighlightField::new("*")],
+            pre_tag: "<em>".to_string(),
+            post_tag: "</em>".to_string(),
+            require_field_match: false,
+        }
+    }
+}
+
+impl HighlightOptions {
+    /// Fragments of the passage text and whole titles, source names, file paths and ids.
+    pub fn carrel_fields() -> Self {
+        HighlightOptions {
+            fields: vec![
+                HighlightField::fragments("text", 150, 3),
+                HighlightField::fragments("context", 150, 1),
+                HighlightField::whole("title"),
+                HighlightField::whole("sourceName"),
+                HighlightField::whole("filePath"),
+                HighlightField::whole("uniqueId"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    pub fn with_tags(mut self, pre_tag: &str, post_tag: &str) -> Self {
+        self.pre_tag = pre_tag.to_string();
+        self.post_tag = post_tag.to_string();
+        self
+    }
+
+    /// Use marker tags that cannot collide with the text, for locating highlights with
+    /// DistantElasticSearchResult::highlight_spans.
+    pub fn with_offset_markers(self) -> Self {
+        self.with_tags(OFFSET_MARKER_PRE_TAG, OFFSET_MARKER_POST_TAG)
+    }
+
+    /// The "highlight" section of the search body.
+    pub fn to_json(&self) -> Value {
+        let mut fields = Map::new();
+        for field in &self.fields {
+            let mut options = Map::new();
+            if let Some(fragment_size) = field.fragment_size {
+                options.insert("fragment_size".to_string(), json!(fragment_size));
+            }
+            if let Some(number_of_fragments) = field.number_of_fragments {
+                options.insert("number_of_fragments".to_string(), json!(number_of_fragments));
+            }
+            fields.insert(field.name.clone(), Value::Object(options));
+        }
+        json!({
+            "require_field_match": self.require_field_match,
+            "pre_tags": [self.pre_tag],
+            "post_tags": [self.post_tag],
+            "fields": fields
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use super::{HighlightField, HighlightOptions};
+
+    #[test]
+    fn test_to_json() {
+        let options = HighlightOptions {
+            fields: vec![HighlightField::fragments("text", 150, 3), HighlightField::whole("title")],
+            ..Default::default()
+        }.with_tags("<mark>", "</mark>");
+        assert_eq!(options.to_json(), json!({
+            "require_field_match": false,
+            "pre_tags": ["<mark>"],
+            "post_tags": ["</mark>"],
+            "fields": {
+                "text": { "fragment_size": 150, "number_of_fragments": 3 },
+                "title": { "number_of_fragments": 0 }
+            }
+        }));
+    }
+}
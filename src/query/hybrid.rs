@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+/// Weights of the lexical and semantic rankings when fusing them with reciprocal rank fusion.
+#[derive(Debug, Clone)]
+pub struct HybridOptions {
+    pub lexical_weight: f64,
+
+    pub semantic_weight: f64,
+
+    /// Dampens the advantage of the top ranks, 60 is the value from the original paper.
+    pub rank_constant: f64,
+
+    /// Number of hits taken from each ranking before fusing, raised to the end of the requested page.
+    pub window_size: u32,
+}
+
+impl Default for HybridOptions {
+    fn default() -> Self {
+        HybridOptions {
+            lexical_weight: 1.0,
+            semantic_weight: 1.0,
+            rank_constant: 60.0,
+            window_size: 50,
+        }
+    }
+}
+
+impl HybridOptions {
+    pub fn with_weights(mut self, lexical_weight: f64, semantic_weight: f64) -> Self {
+        self.lexical_weight = lexical_weight;
+        self.semantic_weight = semantic_weight;
+        self
+    }
+
+    pub fn with_rank_constant(mut self, rank_constant: f64) -> Self {
+        self.rank_constant = rank_constant;
+        self
+    }
+
+    pub fn with_window_size(mut self, window_size: u32) -> Self {
+        self.window_size = window_size;
+        self
+    }
+}
+
+/// A document in the fused ranking, with its 0-based rank in each ranking it appeared in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FusedRank {
+    pub key: String,
+
+    pub score: f64,
+
+    pub lexical_rank: Option<usize>,
+
+    pub semantic_rank: Option<usize>,
+}
+
+/// Fuse two rankings of document keys, best first. Ties keep the lexical order.
+pub fn reciprocal_rank_fusion(lexical: &[String], semantic: &[String], options: &HybridOptions) -> Vec<FusedRank> {
+    let mut fused: Vec<FusedRank> = Vec::new();
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    for (ranking, weight, is_lexical) in [(lexical, options.lexical_weight, true), (semantic, options.semantic_weight, false)] {
+        for (rank, key) in ranking.iter().enumerate() {
+            let position = *positions.entry(key.as_str()).or_insert_with(|| {
+                fused.push(FusedRank { key: key.clone(), score: 0.0, lexical_rank: None, semantic_rank: None });
+                fused.len() - 1
+            });
+            let entry = &mut fused[position];
+            // ranks are 1-based in the formula
+            entry.score += weight / (options.rank_constant + rank as f64 + 1.0);
+            if is_lexical {
+                entry.lexical_rank = Some(rank);
+            } else {
+                entry.semantic_rank = Some(rank);
+            }
+        }
+    }
+    fused.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    fused
+}
+
+#[cfg(test)]
+mod test {
+    use super::{reciprocal_rank_fusion, HybridOptions};
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let fused = reciprocal_rank_fusion(&keys(&["a", "b", "c"]), &keys(&["c", "d", "b"]), &HybridOptions::default());
+        let order: Vec<&str> = fused.iter().map(|rank| rank.key.as_str()).collect();
+        assert_eq!(order, vec!["c", "b", "a", "d"]);
+        assert_eq!(fused[0].lexical_rank, Some(2));
+        assert_eq!(fused[0].semantic_rank, Some(0));
+        assert_eq!(fused[2].semantic_rank, None);
+        assert!((fused[2].score - 1.0 / 61.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion_weights() {
+        let options = HybridOptions::default().with_weights(0.0, 1.0);
+        let fused = reciprocal_rank_fusion(&keys(&["a", "b"]), &keys(&["b"]), &options);
+        assert_eq!(fused[0].key, "b");
+        assert_eq!(fused[1].score, 0.0);
+    }
+}
@@ -0,0 +1,239 @@
+pub mod search_mode;
+pub mod relevance;
+pub mod facets;
+pub mod highlight;
+pub mod more_like_this;
+pub mod hybrid;
+
+use carrel_commons::generic::api::query::v1::SearchQuery;
+use serde_json::{json, Value};
+use crate::language::{expand_language_fields, Language};
+use crate::query::facets::{build_aggregations, FacetRequest};
+use crate::query::highlight::HighlightOptions;
+use crate::query::relevance::RelevanceProfile;
+use crate::query::search_mode::SearchMode;
+
+/// Options controlling how a SearchQuery is translated into the search request body.
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    pub mode: SearchMode,
+
+    /// Name of a relevance profile registered on the DistantClient.
+    pub relevance_profile: Option<String>,
+
+    pub facets: Vec<FacetRequest>,
+
+    pub highlight: HighlightOptions,
+
+    /// Score multiplier per index when searching several indices, e.g. to favour the current project.
+    pub index_boosts: Vec<(String, f32)>,
+
+    /// Count all hits exactly instead of stopping at 10,000 with a "gte" relation.
+    pub track_total_hits: bool,
+
+    /// Also search the language subfields of the query fields.
+    pub languages: Vec<Language>,
+}
+
+impl SearchOptions {
+    pub fn with_mode(mut self, mode: SearchMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_relevance_profile(mut self, relevance_profile: &str) -> Self {
+        self.relevance_profile = Some(relevance_profile.to_string());
+        self
+    }
+
+    pub fn with_facets(mut self, facets: Vec<FacetRequest>) -> Self {
+        self.facets = facets;
+        self
+    }
+
+    pub fn with_highlight(mut self, highlight: HighlightOptions) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    pub fn with_track_total_hits(mut self, track_total_hits: bool) -> Self {
+        self.track_total_hits = track_total_hits;
+        self
+    }
+
+    pub fn with_languages(mut self, languages: Vec<Language>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    pub fn with_index_boost(mut self, index_name: &str, boost: f32) -> Self {
+        self.index_boosts.retain(|(existing, _)| existing != index_name);
+        self.index_boosts.push((index_name.to_string(), boost));
+        self
+    }
+}
+
+/// One search of a multi search request.
+#[derive(Debug, Clone)]
+pub struct MultiSearchRequest {
+    pub index_names: Vec<String>,
+
+    pub search_query: SearchQuery,
+
+    pub search_options: SearchOptions,
+}
+
+impl MultiSearchRequest {
+    pub fn new(index_name: &str, search_query: SearchQuery) -> Self {
+        MultiSearchRequest {
+            index_names: vec![index_name.to_string()],
+            search_query,
+            search_options: SearchOptions::default(),
+        }
+    }
+
+    pub fn with_search_options(mut self, search_options: SearchOptions) -> Self {
+        self.search_options = search_options;
+        self
+    }
+}
+
+/// The query clause for the global filter of the SearchQuery.
+pub fn build_query(search_query: &SearchQuery, options: &SearchOptions, relevance: Option<&RelevanceProfile>) -> Value {
+    let mut query_text = String::new();
+    let mut query_fields = Vec::new();
+    if let Some(filter) = &search_query.filter {
+        query_text = filter.global_filter.clone().unwrap_or_default();
+        query_fields = filter.global_filter_fields.clone();
+    }
+    if let Some(relevance) = relevance {
+        query_fields = relevance.boost_fields(&query_fields);
+    }
+    // after boosting, so the language subfields carry the boost of their field.
+    // keyword values are not copied into the language subfields
+    if !options.languages.is_empty() && options.mode.resolve(&query_text) != SearchMode::Exact {
+        query_fields = expand_language_fields(&query_fields, &options.languages);
+    }
+    let query_clause = options.mode.query_clause(&query_text, &query_fields);
+    match relevance {
+        Some(relevance) => relevance.wrap_query(query_clause),
+        None => query_clause,
+    }
+}
+
+/// The search request body for the SearchQuery.
+pub fn build_search_body(search_query: &SearchQuery, options: &SearchOptions, relevance: Option<&RelevanceProfile>) -> Value {
+    let sort_json = match &search_query.sort {
+        Some(sort) => {
+            let sort_field = sort.field.clone();
+            let sort_order = sort.order.clone();
+            json!({ sort_field: { "order": sort_order } })
+        }
+        None => {
+            json!({})
+        }
+    };
+
+    let mut body = json!({
+        "size": search_query.length,
+        "from": search_query.offset,
+        "query": build_query(search_query, options, relevance),
+        "sort": [sort_json],
+        "highlight": options.highlight.to_json()
+    });
+    if !options.facets.is_empty() {
+        body["aggs"] = build_aggregations(&options.facets);
+    }
+    if options.track_total_hits {
+        body["track_total_hits"] = json!(true);
+    }
+    body
+}
+
+#[cfg(test)]
+mod test {
+    use carrel_commons::generic::api::query::v1::{SearchFilter, SearchQuery};
+    use serde_json::json;
+    use crate::language::Language;
+    use crate::query::facets::FacetRequest;
+    use crate::query::relevance::RelevanceProfile;
+    use crate::query::search_mode::SearchMode;
+    use super::{build_search_body, SearchOptions};
+
+    fn search_query(text: &str) -> SearchQuery {
+        SearchQuery {
+            filter: Some(SearchFilter {
+                global_filter: Some(text.to_string()),
+                global_filter_fields: vec!["text".to_string(), "title".to_string()],
+                ..Default::default()
+            }),
+            offset: 0,
+            length: 10,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_body_is_fuzzy() {
+        let body = build_search_body(&search_query("appl"), &SearchOptions::default(), None);
+        assert_eq!(body["query"], json!({
+            "multi_match": { "query": "appl", "fields": ["text", "title"], "fuzziness": "AUTO" }
+        }));
+        assert_eq!(body["size"], 10);
+    }
+
+    #[test]
+    fn test_relevance_profile_body() {
+        let relevance = RelevanceProfile::carrel_default();
+        let body = build_search_body(&search_query("appl"), &SearchOptions::default(), Some(&relevance));
+        assert_eq!(body["query"]["multi_match"]["fields"], json!(["text", "title^3"]));
+    }
+
+    #[test]
+    fn test_facets_body() {
+        let options = SearchOptions::default().with_facets(FacetRequest::carrel_defaults());
+        let body = build_search_body(&search_query("appl"), &options, None);
+        assert_eq!(body["aggs"]["material_type"], json!({ "terms": { "field": "materialType", "size": 20 } }));
+        assert!(build_search_body(&search_query("appl"), &SearchOptions::default(), None).get("aggs").is_none());
+    }
+
+    #[test]
+    fn test_track_total_hits_body() {
+        let options = SearchOptions::default().with_track_total_hits(true);
+        assert_eq!(build_search_body(&search_query("appl"), &options, None)["track_total_hits"], true);
+        assert!(build_search_body(&search_query("appl"), &SearchOptions::default(), None).get("track_total_hits").is_none());
+    }
+
+    #[test]
+    fn test_language_fields_body() {
+        let options = SearchOptions::default().with_languages(vec![Language::German]);
+        let body = build_search_body(&search_query("buch"), &options, None);
+        assert_eq!(body["query"]["multi_match"]["fields"], json!(["text", "title", "text_de", "title_de"]));
+        let exact = options.with_mode(SearchMode::Exact);
+        assert_eq!(build_search_body(&search_query("buch"), &exact, None)["query"]["bool"]["should"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_language_fields_keep_boosts() {
+        let relevance = RelevanceProfile::carrel_default();
+        let options = SearchOptions::default().with_languages(vec![Language::English]);
+        let body = build_search_body(&search_query("book"), &options, Some(&relevance));
+        assert_eq!(body["query"]["multi_match"]["fields"], json!(["text", "title^3", "text_en", "title_en^3"]));
+    }
+
+    #[test]
+    fn test_query_string_body() {
+        let options = SearchOptions::default().with_mode(SearchMode::QueryString);
+        let body = build_search_body(&search_query("apple OR pear NOT banana"), &options, None);
+        assert_eq!(body["query"]["simple_query_string"]["query"], "apple | pear -banana");
+        assert_eq!(body["query"]["simple_query_string"]["fields"], json!(["text", "title"]));
+    }
+
+    #[test]
+    fn test_phrase_prefix_body() {
+        let options = SearchOptions::default().with_mode(SearchMode::PhrasePrefix);
+        let body = build_search_body(&search_query("artificial intel"), &options, None);
+        assert_eq!(body["query"]["multi_match"]["type"], "phrase_prefix");
+        assert_eq!(body["query"]["multi_match"]["query"], "artificial intel");
+    }
+}
@@ -0,0 +1,83 @@
+use serde_json::{json, Value};
+
+/// What related passages are searched for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoreLikeThisSource {
+    /// An indexed passage by id, which is excluded from the results.
+    Document(String),
+    /// Free text, e.g. a selection in the reader.
+    Text(String),
+}
+
+/// Tuning of the more_like_this query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoreLikeThisOptions {
+    pub fields: Vec<String>,
+
+    /// Minimum frequency of a term in the source for it to be used.
+    pub min_term_freq: u32,
+
+    /// Minimum number of documents a term must occur in for it to be used.
+    pub min_doc_freq: u32,
+
+    pub max_query_terms: u32,
+
+    pub minimum_should_match: String,
+
+    pub size: usize,
+}
+
+impl Default for MoreLikeThisOptions {
+    fn default() -> Self {
+        MoreLikeThisOptions {
+            fields: vec!["text".to_string(), "title".to_string()],
+            min_term_freq: 1,
+            min_doc_freq: 2,
+            max_query_terms: 25,
+            minimum_should_match: "30%".to_string(),
+            size: 10,
+        }
+    }
+}
+
+impl MoreLikeThisOptions {
+    /// The more_like_this query, `index_name` is the full name of the index holding the source document.
+    pub fn to_query(&self, index_name: &str, source: &MoreLikeThisSource) -> Value {
+        let like = match source {
+            MoreLikeThisSource::Document(id) => json!([{ "_index": index_name, "_id": id }]),
+            MoreLikeThisSource::Text(text) => json!([text]),
+        };
+        json!({
+            "more_like_this": {
+                "fields": self.fields,
+                "like": like,
+                "min_term_freq": self.min_term_freq,
+                "min_doc_freq": self.min_doc_freq,
+                "max_query_terms": self.max_query_terms,
+                "minimum_should_match": self.minimum_should_match,
+                "include": false
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use super::{MoreLikeThisOptions, MoreLikeThisSource};
+
+    #[test]
+    fn test_document_query_excludes_source() {
+        let query = MoreLikeThisOptions::default().to_query("library", &MoreLikeThisSource::Document("a".to_string()));
+        assert_eq!(query["more_like_this"]["like"], json!([{ "_index": "library", "_id": "a" }]));
+        assert_eq!(query["more_like_this"]["include"], false);
+    }
+
+    #[test]
+    fn test_text_query() {
+        let options = MoreLikeThisOptions { min_doc_freq: 1, ..Default::default() };
+        let query = options.to_query("library", &MoreLikeThisSource::Text("apple pie".to_string()));
+        assert_eq!(query["more_like_this"]["like"], json!(["apple pie"]));
+        assert_eq!(query["more_like_this"]["min_doc_freq"], 1);
+    }
+}
@@ -0,0 +1,157 @@
+use serde_json::{json, Value};
+
+/// Named relevance tuning applied to a search: field boosts, a recency decay and
+/// boosts per material type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RelevanceProfile {
+    /// Boost per field name, e.g. ("title", 3.0). Fields without a boost keep a weight of 1.
+    pub field_boosts: Vec<(String, f32)>,
+
+    pub recency_decay: Option<RecencyDecay>,
+
+    /// Score multiplier per CarrelSearchResultItem material_type.
+    pub material_type_boosts: Vec<(i32, f32)>,
+}
+
+/// Gaussian decay of the score with the distance of a date field from now.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecencyDecay {
+    pub field: String,
+
+    /// Distance at which the score is multiplied by `decay`, e.g. "365d".
+    pub scale: String,
+
+    /// Distance within which documents are not decayed, e.g. "30d".
+    pub offset: String,
+
+    pub decay: f64,
+}
+
+impl RelevanceProfile {
+    /// Titles and tags outrank incidental mentions in the passage text.
+    pub fn carrel_default() -> Self {
+        RelevanceProfile {
+            field_boosts: vec![
+                ("title".to_string(), 3.0),
+                ("tags".to_string(), 2.0),
+                ("sourceName".to_string(), 1.5),
+                ("text".to_string(), 1.0),
+            ],
+            ..Default::default()
+        }
+    }
+
+    pub fn with_field_boost(mut self, field: &str, boost: f32) -> Self {
+        self.field_boosts.retain(|(name, _)| name != field);
+        self.field_boosts.push((field.to_string(), boost));
+        self
+    }
+
+    pub fn with_recency_decay(mut self, recency_decay: RecencyDecay) -> Self {
+        self.recency_decay = Some(recency_decay);
+        self
+    }
+
+    pub fn with_material_type_boost(mut self, material_type: i32, boost: f32) -> Self {
+        self.material_type_boosts.retain(|(existing, _)| *existing != material_type);
+        self.material_type_boosts.push((material_type, boost));
+        self
+    }
+
+    // the fields with their boosts, the profile fields are searched when none are given
+    pub fn boost_fields(&self, fields: &[String]) -> Vec<String> {
+        if fields.is_empty() {
+            return self.field_boosts.iter().map(|(field, boost)| boosted_field(field, *boost)).collect();
+        }
+        fields.iter()
+            .map(|field| {
+                // keep boosts given explicitly in the query
+                if field.contains('^') {
+                    return field.clone();
+                }
+                match self.field_boosts.iter().find(|(name, _)| name == field) {
+                    Some((name, boost)) => boosted_field(name, *boost),
+                    None => field.clone(),
+                }
+            })
+            .collect()
+    }
+
+    // wrap the query in a function_score when the profile has recency or material type boosts
+    pub fn wrap_query(&self, query: Value) -> Value {
+        let mut functions: Vec<Value> = self.material_type_boosts.iter()
+            .map(|(material_type, boost)| json!({
+                "filter": { "term": { "materialType": material_type } },
+                "weight": boost
+            }))
+            .collect();
+        if let Some(decay) = &self.recency_decay {
+            functions.push(json!({
+                "gauss": {
+                    decay.field.clone(): {
+                        "origin": "now",
+                        "scale": decay.scale,
+                        "offset": decay.offset,
+                        "decay": decay.decay
+                    }
+                }
+            }));
+        }
+        if functions.is_empty() {
+            return query;
+        }
+        json!({
+            "function_score": {
+                "query": query,
+                "functions": functions,
+                "score_mode": "multiply",
+                "boost_mode": "multiply"
+            }
+        })
+    }
+}
+
+fn boosted_field(field: &str, boost: f32) -> String {
+    if boost == 1.0 {
+        field.to_string()
+    } else {
+        format!("{}^{}", field, boost)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use super::{RecencyDecay, RelevanceProfile};
+
+    #[test]
+    fn test_boost_fields() {
+        let profile = RelevanceProfile::carrel_default();
+        assert_eq!(profile.boost_fields(&[]), vec!["title^3", "tags^2", "sourceName^1.5", "text"]);
+        let fields = vec!["text".to_string(), "title".to_string(), "context".to_string(), "tags^5".to_string()];
+        assert_eq!(profile.boost_fields(&fields), vec!["text", "title^3", "context", "tags^5"]);
+    }
+
+    #[test]
+    fn test_wrap_query_without_functions() {
+        let query = json!({ "match_all": {} });
+        assert_eq!(RelevanceProfile::carrel_default().wrap_query(query.clone()), query);
+    }
+
+    #[test]
+    fn test_wrap_query_with_functions() {
+        let profile = RelevanceProfile::default()
+            .with_material_type_boost(2, 1.5)
+            .with_recency_decay(RecencyDecay {
+                field: "modified".to_string(),
+                scale: "365d".to_string(),
+                offset: "30d".to_string(),
+                decay: 0.5,
+            });
+        let wrapped = profile.wrap_query(json!({ "match_all": {} }));
+        let functions = wrapped["function_score"]["functions"].as_array().unwrap();
+        assert_eq!(functions.len(), 2);
+        assert_eq!(functions[0]["filter"]["term"]["materialType"], 2);
+        assert_eq!(functions[1]["gauss"]["modified"]["scale"], "365d");
+    }
+}
@@ -0,0 +1,194 @@
+use serde_json::{json, Value};
+
+/// How the text of the global filter is matched against the global filter fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// Pick a mode from the query text: a quoted text is a phrase, a trailing `*`
+    /// a phrase prefix, AND/OR/NOT or `+ - |` operators a query string, anything else fuzzy.
+    Auto,
+    /// multi_match with fuzziness AUTO, the text is taken literally.
+    #[default]
+    Fuzzy,
+    /// Words in the given order.
+    Phrase,
+    /// Words in the given order, the last one as a prefix, for search as you type.
+    PhrasePrefix,
+    /// simple_query_string supporting AND/OR/NOT, `+ - |`, quoted phrases, `*` prefixes and parentheses.
+    QueryString,
+    /// The whole text equals the keyword value of a field.
+    Exact,
+}
+
+impl SearchMode {
+    // the concrete mode for the query text
+    pub fn resolve(self, query_text: &str) -> SearchMode {
+        if self != SearchMode::Auto {
+            return self;
+        }
+        let trimmed = query_text.trim();
+        if trimmed.len() >= 2 && trimmed.starts_with('"') && trimmed.ends_with('"') && trimmed.matches('"').count() == 2 {
+            return SearchMode::Phrase;
+        }
+        let has_operator = trimmed.split_whitespace().any(|word| {
+            matches!(word, "AND" | "OR" | "NOT") || word.starts_with('+') || word.starts_with('-') || word == "|"
+        });
+        if has_operator || trimmed.contains('"') {
+            return SearchMode::QueryString;
+        }
+        if trimmed.ends_with('*') {
+            return SearchMode::PhrasePrefix;
+        }
+        SearchMode::Fuzzy
+    }
+
+    // the query clause matching the text on the fields
+    pub fn query_clause(self, query_text: &str, fields: &[String]) -> Value {
+        match self.resolve(query_text) {
+            SearchMode::Auto | SearchMode::Fuzzy => json!({
+                "multi_match": {
+                    "query": query_text,
+                    "fields": fields,
+                    "fuzziness": "AUTO"
+                }
+            }),
+            SearchMode::Phrase => json!({
+                "multi_match": {
+                    "query": query_text.trim().trim_matches('"'),
+                    "fields": fields,
+                    "type": "phrase"
+                }
+            }),
+            SearchMode::PhrasePrefix => json!({
+                "multi_match": {
+                    "query": query_text.trim().trim_end_matches('*'),
+                    "fields": fields,
+                    "type": "phrase_prefix"
+                }
+            }),
+            SearchMode::QueryString => json!({
+                "simple_query_string": {
+                    "query": sanitize_query_string(query_text),
+                    "fields": fields,
+                    "default_operator": "and",
+                    "flags": "AND|OR|NOT|PHRASE|PREFIX|PRECEDENCE|WHITESPACE|ESCAPE"
+                }
+            }),
+            SearchMode::Exact => json!({
+                "bool": {
+                    "should": fields.iter()
+                        .map(|field| json!({ "term": { keyword_field(field): query_text.trim() } }))
+                        .collect::<Vec<Value>>(),
+                    "minimum_should_match": 1
+                }
+            }),
+        }
+    }
+}
+
+// the keyword subfield of a field, keeping any boost suffix out of the name
+fn keyword_field(field: &str) -> String {
+    let name = field.split('^').next().unwrap_or(field);
+    if name.ends_with(".keyword") {
+        name.to_string()
+    } else {
+        format!("{}.keyword", name)
+    }
+}
+
+/// Translate AND/OR/NOT into simple_query_string operators and escape what would
+/// otherwise be unbalanced: backslashes, a dangling quote and unmatched parentheses.
+pub fn sanitize_query_string(text: &str) -> String {
+    // "a NOT b" becomes "a -b", a translated prefix operator attaches to the next word
+    let mut translated: Vec<String> = Vec::new();
+    let mut prefix: Option<&str> = None;
+    for word in text.split_whitespace() {
+        match word {
+            "AND" => prefix = Some("+"),
+            "NOT" => prefix = Some("-"),
+            "OR" => translated.push("|".to_string()),
+            word => translated.push(format!("{}{}", prefix.take().unwrap_or(""), word.replace('\\', "\\\\"))),
+        }
+    }
+    translated.extend(prefix.map(str::to_string));
+    let mut query = translated.join(" ");
+    if query.matches('"').count() % 2 == 1 {
+        if let Some(position) = query.rfind('"') {
+            query.insert(position, '\\');
+        }
+    }
+    if !parentheses_balanced(&query) {
+        query = query.replace('(', "\\(").replace(')', "\\)");
+    }
+    query
+}
+
+fn parentheses_balanced(text: &str) -> bool {
+    let mut depth = 0i32;
+    for c in text.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return false;
+        }
+    }
+    depth == 0
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use super::{sanitize_query_string, SearchMode};
+
+    #[test]
+    fn test_default_is_literal() {
+        assert_eq!(SearchMode::default(), SearchMode::Fuzzy);
+        assert_eq!(SearchMode::default().resolve("ai -ethics"), SearchMode::Fuzzy);
+    }
+
+    #[test]
+    fn test_resolve_auto() {
+        assert_eq!(SearchMode::Auto.resolve("banana"), SearchMode::Fuzzy);
+        assert_eq!(SearchMode::Auto.resolve("\"artificial intelligence\""), SearchMode::Phrase);
+        assert_eq!(SearchMode::Auto.resolve("artif*"), SearchMode::PhrasePrefix);
+        assert_eq!(SearchMode::Auto.resolve("ai AND ethics"), SearchMode::QueryString);
+        assert_eq!(SearchMode::Auto.resolve("ai -ethics"), SearchMode::QueryString);
+        assert_eq!(SearchMode::Exact.resolve("ai AND ethics"), SearchMode::Exact);
+    }
+
+    #[test]
+    fn test_sanitize_query_string() {
+        assert_eq!(sanitize_query_string("ai AND ethics"), "ai +ethics");
+        assert_eq!(sanitize_query_string("ai OR ml"), "ai | ml");
+        assert_eq!(sanitize_query_string("ai NOT ethics"), "ai -ethics");
+        assert_eq!(sanitize_query_string("C++ programming"), "C++ programming");
+        assert_eq!(sanitize_query_string("pro - con"), "pro - con");
+        assert_eq!(sanitize_query_string("\"open quote"), "\\\"open quote");
+        assert_eq!(sanitize_query_string("(a | b"), "\\(a | b");
+        assert_eq!(sanitize_query_string("(a | b) +c"), "(a | b) +c");
+    }
+
+    #[test]
+    fn test_phrase_clause() {
+        let fields = vec!["text".to_string()];
+        assert_eq!(SearchMode::Auto.query_clause("\"apple pie\"", &fields), json!({
+            "multi_match": { "query": "apple pie", "fields": ["text"], "type": "phrase" }
+        }));
+    }
+
+    #[test]
+    fn test_exact_clause() {
+        let fields = vec!["title^3".to_string(), "tags".to_string()];
+        assert_eq!(SearchMode::Exact.query_clause("Apple", &fields), json!({
+            "bool": {
+                "should": [
+                    { "term": { "title.keyword": "Apple" } },
+                    { "term": { "tags.keyword": "Apple" } }
+                ],
+                "minimum_should_match": 1
+            }
+        }));
+    }
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Per-call options for DistantClient operations.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Overrides the client-wide request timeout. Also sent to Elasticsearch as the
+    /// `timeout` parameter where the API supports one.
+    pub timeout: Option<Duration>,
+
+    /// Aborts the operation when cancelled. Long running operations stop between
+    /// batches and cancel their server-side task.
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+impl RequestOptions {
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token.as_ref().map_or(false, CancellationToken::is_cancelled)
+    }
+}
+
+// Elasticsearch time unit string for the duration
+pub fn to_es_time(duration: Duration) -> String {
+    format!("{}ms", duration.as_millis())
+}
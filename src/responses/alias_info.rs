@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// Row of the `_cat/aliases` API.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AliasInfo {
+    #[serde(rename = "alias")]
+    pub alias: String,
+
+    #[serde(rename = "index")]
+    pub index: String,
+
+    #[serde(rename = "filter")]
+    pub filter: Option<String>,
+
+    #[serde(rename = "routing.index")]
+    pub routing_index: Option<String>,
+
+    #[serde(rename = "routing.search")]
+    pub routing_search: Option<String>,
+
+    #[serde(rename = "is_write_index")]
+    pub is_write_index: Option<String>,
+}
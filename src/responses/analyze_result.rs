@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// Response of the `_analyze` API.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalyzeResult {
+    #[serde(rename = "tokens", default)]
+    pub tokens: Vec<AnalyzeToken>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyzeToken {
+    #[serde(rename = "token")]
+    pub token: String,
+
+    #[serde(rename = "start_offset")]
+    pub start_offset: u64,
+
+    #[serde(rename = "end_offset")]
+    pub end_offset: u64,
+
+    /// e.g. "<ALPHANUM>", or "SYNONYM" for tokens added by a synonym filter.
+    #[serde(rename = "type")]
+    pub token_type: String,
+
+    #[serde(rename = "position")]
+    pub position: u64,
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_analyze_result() {
+        let json = r#"{"tokens":[{"token":"ai","start_offset":0,"end_offset":2,"type":"<ALPHANUM>","position":0},
+            {"token":"artificial","start_offset":0,"end_offset":2,"type":"SYNONYM","position":0}]}"#;
+        let result: super::AnalyzeResult = serde_json::from_str(json).unwrap();
+        assert_eq!(result.tokens.len(), 2);
+        assert_eq!(result.tokens[1].token_type, "SYNONYM");
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::responses::error_response::ErrorCause;
+
+/// Response of the `_bulk` API. A 200 response can still contain failed items.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkResponse {
+    #[serde(rename = "errors", default)]
+    pub errors: bool,
+
+    /// One entry per action, keyed by the action name ("index", "delete", ...).
+    #[serde(rename = "items", default)]
+    pub items: Vec<HashMap<String, BulkItem>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkItem {
+    #[serde(rename = "_id", default)]
+    pub id: Option<String>,
+
+    #[serde(rename = "status")]
+    pub status: u16,
+
+    #[serde(rename = "error", default)]
+    pub error: Option<ErrorCause>,
+}
+
+/// A bulk action that was rejected. DistantClient::index reports the position of the entry among those indexed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkItemFailure {
+    pub position: usize,
+
+    pub id: Option<String>,
+
+    pub status: u16,
+
+    pub error_type: String,
+
+    pub reason: String,
+}
+
+impl BulkResponse {
+    // the failed actions, in the order of the request
+    pub fn failures(&self) -> Vec<BulkItemFailure> {
+        self.items.iter()
+            .enumerate()
+            .flat_map(|(position, actions)| actions.values().map(move |item| (position, item)))
+            .filter(|(_, item)| item.error.is_some() || item.status >= 300)
+            .map(|(position, item)| {
+                let error = item.error.as_ref();
+                BulkItemFailure {
+                    position,
+                    id: item.id.clone(),
+                    status: item.status,
+                    error_type: error.map(|error| error.error_type.clone()).unwrap_or_default(),
+                    reason: error.and_then(|error| error.reason.clone()).unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BulkResponse;
+
+    #[test]
+    fn test_mixed_bulk_response() {
+        let json = r#"{"took":30,"errors":true,"items":[
+            {"index":{"_index":"library","_type":"pdf","_id":"a","_version":1,"result":"created","status":201}},
+            {"index":{"_index":"library","_type":"pdf","_id":"b","status":429,"error":{"type":"es_rejected_execution_exception","reason":"rejected execution of coordinating operation"}}},
+            {"index":{"_index":"library","_type":"pdf","_id":"c","status":400,"error":{"type":"mapper_parsing_exception","reason":"failed to parse field [modified]"}}}]}"#;
+        let response: BulkResponse = serde_json::from_str(json).unwrap();
+        assert!(response.errors);
+        let failures = response.failures();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].position, 1);
+        assert_eq!(failures[0].id.as_deref(), Some("b"));
+        assert_eq!(failures[0].status, 429);
+        assert_eq!(failures[1].error_type, "mapper_parsing_exception");
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::responses::index_info::HealthStatus;
+
+/// Response of the `_cluster/health` API.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClusterHealth {
+    #[serde(rename = "cluster_name")]
+    pub cluster_name: String,
+
+    #[serde(rename = "status")]
+    pub status: HealthStatus,
+
+    #[serde(rename = "timed_out")]
+    pub timed_out: bool,
+
+    #[serde(rename = "number_of_nodes")]
+    pub number_of_nodes: u32,
+
+    #[serde(rename = "number_of_data_nodes")]
+    pub number_of_data_nodes: u32,
+
+    #[serde(rename = "active_primary_shards")]
+    pub active_primary_shards: u32,
+
+    #[serde(rename = "active_shards")]
+    pub active_shards: u32,
+
+    #[serde(rename = "relocating_shards")]
+    pub relocating_shards: u32,
+
+    #[serde(rename = "initializing_shards")]
+    pub initializing_shards: u32,
+
+    #[serde(rename = "unassigned_shards")]
+    pub unassigned_shards: u32,
+
+    #[serde(rename = "number_of_pending_tasks")]
+    pub number_of_pending_tasks: u32,
+
+    #[serde(rename = "active_shards_percent_as_number")]
+    pub active_shards_percent: f64,
+
+    /// Only present when requested with `level=indices`.
+    #[serde(rename = "indices", default)]
+    pub indices: HashMap<String, IndexHealth>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexHealth {
+    #[serde(rename = "status")]
+    pub status: HealthStatus,
+
+    #[serde(rename = "number_of_shards")]
+    pub number_of_shards: u32,
+
+    #[serde(rename = "number_of_replicas")]
+    pub number_of_replicas: u32,
+
+    #[serde(rename = "active_primary_shards")]
+    pub active_primary_shards: u32,
+
+    #[serde(rename = "active_shards")]
+    pub active_shards: u32,
+
+    #[serde(rename = "relocating_shards")]
+    pub relocating_shards: u32,
+
+    #[serde(rename = "initializing_shards")]
+    pub initializing_shards: u32,
+
+    #[serde(rename = "unassigned_shards")]
+    pub unassigned_shards: u32,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::responses::index_info::HealthStatus;
+    use super::ClusterHealth;
+
+    #[test]
+    fn test_parse_cluster_health() {
+        let json = r#"{"cluster_name":"docker-cluster","status":"yellow","timed_out":false,"number_of_nodes":1,"number_of_data_nodes":1,"active_primary_shards":5,"active_shards":5,"relocating_shards":0,"initializing_shards":0,"unassigned_shards":2,"delayed_unassigned_shards":0,"number_of_pending_tasks":0,"number_of_in_flight_fetch":0,"task_max_waiting_in_queue_millis":0,"active_shards_percent_as_number":71.42857142857143,
+            "indices":{"library":{"status":"yellow","number_of_shards":1,"number_of_replicas":1,"active_primary_shards":1,"active_shards":1,"relocating_shards":0,"initializing_shards":0,"unassigned_shards":1}}}"#;
+        let health: ClusterHealth = serde_json::from_str(json).unwrap();
+        assert_eq!(health.status, HealthStatus::Yellow);
+        assert_eq!(health.unassigned_shards, 2);
+        assert_eq!(health.indices["library"].unassigned_shards, 1);
+    }
+}
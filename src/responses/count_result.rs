@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use crate::responses::search_result::Shards;
+
+/// Response of the `_count` API.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CountResult {
+    #[serde(rename = "count")]
+    pub count: u64,
+
+    #[serde(rename = "_shards")]
+    pub shards: Option<Shards>,
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Summary of a finished delete by query task.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DeleteByQueryReport {
+    #[serde(rename = "took", default)]
+    pub took: i64,
+
+    #[serde(rename = "timed_out", default)]
+    pub timed_out: bool,
+
+    #[serde(rename = "total", default)]
+    pub total: i64,
+
+    #[serde(rename = "deleted", default)]
+    pub deleted: i64,
+
+    #[serde(rename = "batches", default)]
+    pub batches: i64,
+
+    #[serde(rename = "version_conflicts", default)]
+    pub version_conflicts: i64,
+
+    #[serde(rename = "noops", default)]
+    pub noops: i64,
+
+    #[serde(rename = "failures", default)]
+    pub failures: Vec<Value>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::DeleteByQueryReport;
+
+    #[test]
+    fn test_parse_delete_by_query_report() {
+        let json = r#"{"took":147,"timed_out":false,"total":3,"deleted":3,"batches":1,"version_conflicts":0,"noops":0,"retries":{"bulk":0,"search":0},"throttled_millis":0,"requests_per_second":-1.0,"throttled_until_millis":0,"failures":[]}"#;
+        let report: DeleteByQueryReport = serde_json::from_str(json).unwrap();
+        assert_eq!(report.total, 3);
+        assert_eq!(report.deleted, 3);
+        assert!(report.failures.is_empty());
+    }
+}
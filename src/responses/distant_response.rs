@@ -0,0 +1,25 @@
+use carrel_commons::carrel::shared::search::v1::CarrelSearchResponse;
+use crate::responses::facets::Facet;
+use crate::responses::hybrid_result::ComponentScores;
+
+/// A CarrelSearchResponse together with what the shared search types have no fields for.
+/// The vectors are in the order of the results.
+#[derive(Debug)]
+pub struct DistantSearchResponse {
+    pub response: CarrelSearchResponse,
+
+    /// The library (index name local to the namespace) each result came from.
+    pub libraries: Vec<String>,
+
+    /// The requested facets, empty when none were requested.
+    pub facets: Vec<Facet>,
+
+    /// Lexical and semantic scores of each result, for hybrid searches only.
+    pub component_scores: Option<Vec<ComponentScores>>,
+}
+
+impl From<DistantSearchResponse> for CarrelSearchResponse {
+    fn from(response: DistantSearchResponse) -> Self {
+        response.response
+    }
+}
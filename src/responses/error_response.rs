@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// Error body returned by Elasticsearch for failed requests.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    #[serde(rename = "error")]
+    pub error: ErrorCause,
+
+    #[serde(rename = "status")]
+    pub status: u16,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ErrorCause {
+    #[serde(rename = "type", default)]
+    pub error_type: String,
+
+    #[serde(rename = "reason", default)]
+    pub reason: Option<String>,
+
+    #[serde(rename = "index", default)]
+    pub index: Option<String>,
+
+    #[serde(rename = "line", default)]
+    pub line: Option<u32>,
+
+    #[serde(rename = "col", default)]
+    pub col: Option<u32>,
+
+    #[serde(rename = "root_cause", default)]
+    pub root_cause: Vec<ErrorCause>,
+
+    #[serde(rename = "caused_by", default)]
+    pub caused_by: Option<Box<ErrorCause>>,
+}
+
+impl ErrorCause {
+    // the most specific cause, search errors wrap the actual failure in root_cause
+    pub fn innermost(&self) -> &ErrorCause {
+        match (self.root_cause.first(), &self.caused_by) {
+            (Some(root_cause), _) => root_cause,
+            (None, Some(caused_by)) => caused_by.innermost(),
+            (None, None) => self,
+        }
+    }
+}
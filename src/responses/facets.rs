@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Bucket aggregation as returned by terms and date_histogram aggregations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketAggregation {
+    #[serde(rename = "buckets", default)]
+    pub buckets: Vec<Bucket>,
+
+    #[serde(rename = "sum_other_doc_count", default)]
+    pub sum_other_doc_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bucket {
+    #[serde(rename = "key")]
+    pub key: Value,
+
+    #[serde(rename = "key_as_string")]
+    pub key_as_string: Option<String>,
+
+    #[serde(rename = "doc_count")]
+    pub doc_count: i64,
+}
+
+/// Counts of the hits per value of a field, for drill-down in the search sidebar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Facet {
+    pub name: String,
+
+    pub buckets: Vec<FacetBucket>,
+
+    /// Hits with values not in the returned buckets.
+    pub other_count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FacetBucket {
+    /// The value to filter on to drill down.
+    pub value: Value,
+
+    /// Readable value, e.g. the formatted date of a date histogram bucket.
+    pub label: String,
+
+    pub count: i64,
+}
+
+impl Facet {
+    pub fn new(name: String, aggregation: &BucketAggregation) -> Self {
+        Facet {
+            name,
+            buckets: aggregation.buckets.iter()
+                .map(|bucket| FacetBucket {
+                    value: bucket.key.clone(),
+                    label: bucket.key_as_string.clone().unwrap_or_else(|| match &bucket.key {
+                        Value::String(key) => key.clone(),
+                        key => key.to_string(),
+                    }),
+                    count: bucket.doc_count,
+                })
+                .collect(),
+            other_count: aggregation.sum_other_doc_count,
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use crate::responses::search_result::HighlightTags;
+
+/// A highlighted span located in the original field value.
+/// Offsets count characters (not bytes), `end` is exclusive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighlightSpan {
+    pub field: String,
+
+    pub start: usize,
+
+    pub end: usize,
+
+    pub text: String,
+}
+
+/// Locate the highlighted spans of a fragment in the field value it was taken from.
+/// Returns nothing when the fragment, stripped of its tags, is not part of the value.
+pub fn locate_highlights(field: &str, field_value: &str, fragment: &str, tags: &HighlightTags) -> Vec<HighlightSpan> {
+    locate_field_highlights(field, field_value, &[fragment.to_string()], tags)
+}
+
+/// Locate the highlighted spans of all fragments of a field. A text occurring several times in the
+/// value is matched to a different occurrence for each fragment, in the order of the fragments.
+pub fn locate_field_highlights(field: &str, field_value: &str, fragments: &[String], tags: &HighlightTags) -> Vec<HighlightSpan> {
+    let mut spans = vec![];
+    // without both tags the highlighted parts cannot be told apart
+    if tags.pre_tag.is_empty() || tags.post_tag.is_empty() {
+        return spans;
+    }
+    // byte offsets of the occurrences already taken by a fragment
+    let mut claimed: Vec<usize> = vec![];
+    let mut cursor = 0;
+    for fragment in fragments {
+        let (stripped, local_spans) = strip_tags(fragment, tags);
+        if stripped.is_empty() {
+            continue;
+        }
+        // fragments come in the order of the value unless sorted by score, so look after the previous one first
+        let byte_offset = field_value[cursor..].match_indices(stripped.as_str()).map(|(offset, _)| cursor + offset)
+            .chain(field_value.match_indices(stripped.as_str()).map(|(offset, _)| offset))
+            .find(|offset| !claimed.contains(offset));
+        let byte_offset = match byte_offset {
+            Some(byte_offset) => byte_offset,
+            None => continue,
+        };
+        claimed.push(byte_offset);
+        cursor = byte_offset + stripped.len();
+        let fragment_start = field_value[..byte_offset].chars().count();
+        spans.extend(local_spans.into_iter().map(|(start, end)| HighlightSpan {
+            field: field.to_string(),
+            start: fragment_start + start,
+            end: fragment_start + end,
+            text: stripped.chars().skip(start).take(end - start).collect(),
+        }));
+    }
+    spans
+}
+
+// remove the tags from the fragment, returning the text and the character spans that were tagged
+fn strip_tags(fragment: &str, tags: &HighlightTags) -> (String, Vec<(usize, usize)>) {
+    let mut stripped = String::with_capacity(fragment.len());
+    let mut spans = vec![];
+    let mut length = 0;
+    let mut rest = fragment;
+    while let Some(pre_position) = rest.find(&tags.pre_tag) {
+        let before = &rest[..pre_position];
+        stripped.push_str(before);
+        length += before.chars().count();
+        rest = &rest[pre_position + tags.pre_tag.len()..];
+        let post_position = rest.find(&tags.post_tag).unwrap_or(rest.len());
+        let highlighted = &rest[..post_position];
+        stripped.push_str(highlighted);
+        let start = length;
+        length += highlighted.chars().count();
+        spans.push((start, length));
+        rest = &rest[(post_position + tags.post_tag.len()).min(rest.len())..];
+    }
+    stripped.push_str(rest);
+    (stripped, spans)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::responses::search_result::HighlightTags;
+    use super::{locate_field_highlights, locate_highlights, HighlightSpan};
+
+    #[test]
+    fn test_locate_highlights_in_fragment() {
+        let value = "Die Äpfel fallen. An apple a day keeps the doctor away.";
+        let spans = locate_highlights("text", value, "An <em>apple</em> a <em>day</em>", &HighlightTags::default());
+        assert_eq!(spans, vec![
+            HighlightSpan { field: "text".to_string(), start: 21, end: 26, text: "apple".to_string() },
+            HighlightSpan { field: "text".to_string(), start: 29, end: 32, text: "day".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_locate_highlights_with_markers_and_literal_em() {
+        let tags = HighlightTags::offset_markers();
+        let value = "the <em> tag marks emphasis";
+        let fragment = format!("the <em> tag marks {}emphasis{}", tags.pre_tag, tags.post_tag);
+        let spans = locate_highlights("text", value, &fragment, &tags);
+        assert_eq!(spans.len(), 1);
+        assert_eq!((spans[0].start, spans[0].end), (19, 27));
+        assert_eq!(spans[0].text, "emphasis");
+    }
+
+    #[test]
+    fn test_locate_repeated_fragments() {
+        let value = "apple pie. pear tart. apple pie.";
+        let fragments = vec!["<em>apple</em> pie.".to_string(), "<em>apple</em> pie.".to_string()];
+        let spans = locate_field_highlights("text", value, &fragments, &HighlightTags::default());
+        let starts: Vec<usize> = spans.iter().map(|span| span.start).collect();
+        assert_eq!(starts, vec![0, 22]);
+        // out of order fragments still take distinct occurrences
+        let fragments = vec!["<em>apple</em> pie.".to_string(), "<em>pear</em> tart.".to_string(), "<em>apple</em> pie.".to_string()];
+        let starts: Vec<usize> = locate_field_highlights("text", value, &fragments, &HighlightTags::default())
+            .iter().map(|span| span.start).collect();
+        assert_eq!(starts, vec![0, 11, 22]);
+        let fragments = vec!["<em>pear</em> tart.".to_string(), "<em>apple</em> pie.".to_string(), "<em>apple</em> pie.".to_string()];
+        let starts: Vec<usize> = locate_field_highlights("text", value, &fragments, &HighlightTags::default())
+            .iter().map(|span| span.start).collect();
+        assert_eq!(starts, vec![11, 22, 0]);
+    }
+
+    #[test]
+    fn test_empty_tags_give_no_spans() {
+        let tags = HighlightTags { pre_tag: String::new(), post_tag: "</em>".to_string() };
+        assert!(locate_highlights("text", "apple", "apple</em>", &tags).is_empty());
+    }
+
+    #[test]
+    fn test_fragment_not_in_value() {
+        assert!(locate_highlights("text", "apple", "<em>pear</em>", &HighlightTags::default()).is_empty());
+    }
+}
@@ -0,0 +1,43 @@
+use carrel_commons::carrel::shared::search::v1::CarrelSearchResponse;
+use crate::responses::distant_response::DistantSearchResponse;
+use crate::responses::search_result::DistantElasticSearchResult;
+
+/// Scores of a hybrid hit: the fused score is the score of the result, the component
+/// scores are those of the lexical and semantic searches the hit appeared in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentScores {
+    pub fused_score: f64,
+
+    pub lexical_score: Option<f64>,
+
+    pub lexical_rank: Option<usize>,
+
+    /// Cosine similarity of the query and passage embeddings.
+    pub semantic_score: Option<f64>,
+
+    pub semantic_rank: Option<usize>,
+}
+
+/// A page of the fused ranking. The hits carry the fused score.
+#[derive(Debug)]
+pub struct HybridSearchResult {
+    pub result: DistantElasticSearchResult,
+
+    /// Component scores of each hit, in the order of the hits.
+    pub component_scores: Vec<ComponentScores>,
+}
+
+impl HybridSearchResult {
+    pub fn into_distant_response(self) -> DistantSearchResponse {
+        DistantSearchResponse {
+            component_scores: Some(self.component_scores),
+            ..self.result.into_distant_response()
+        }
+    }
+}
+
+impl From<HybridSearchResult> for CarrelSearchResponse {
+    fn from(result: HybridSearchResult) -> Self {
+        result.result.into()
+    }
+}
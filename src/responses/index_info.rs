@@ -11,17 +11,20 @@
 //     let model: [object Object] = serde_json::from_str(&json).unwrap();
 // }
 
-use serde::{Serialize, Deserialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
 pub type Index = Vec<IndexInfo>;
 
+/// Row of the `_cat/indices` API requested with `bytes=b`.
+/// Closed indices report null for health and every statistic.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct IndexInfo {
     #[serde(rename = "health")]
-    pub health: String,
+    pub health: Option<HealthStatus>,
 
     #[serde(rename = "status")]
-    pub status: String,
+    pub status: IndexStatus,
 
     #[serde(rename = "index")]
     pub index: String,
@@ -29,21 +32,80 @@ pub struct IndexInfo {
     #[serde(rename = "uuid")]
     pub uuid: String,
 
-    #[serde(rename = "pri")]
-    pub pri: String,
+    #[serde(rename = "pri", deserialize_with = "deserialize_u64", default)]
+    pub pri: Option<u64>,
 
-    #[serde(rename = "rep")]
-    pub rep: String,
+    #[serde(rename = "rep", deserialize_with = "deserialize_u64", default)]
+    pub rep: Option<u64>,
 
-    #[serde(rename = "docs.count")]
-    pub docs_count: String,
+    #[serde(rename = "docs.count", deserialize_with = "deserialize_u64", default)]
+    pub docs_count: Option<u64>,
 
-    #[serde(rename = "docs.deleted")]
-    pub docs_deleted: String,
+    #[serde(rename = "docs.deleted", deserialize_with = "deserialize_u64", default)]
+    pub docs_deleted: Option<u64>,
 
-    #[serde(rename = "store.size")]
-    pub store_size: String,
+    /// Size in bytes.
+    #[serde(rename = "store.size", deserialize_with = "deserialize_u64", default)]
+    pub store_size: Option<u64>,
 
-    #[serde(rename = "pri.store.size")]
-    pub pri_store_size: String,
+    /// Size in bytes.
+    #[serde(rename = "pri.store.size", deserialize_with = "deserialize_u64", default)]
+    pub pri_store_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HealthStatus {
+    #[serde(rename = "green")]
+    Green,
+
+    #[serde(rename = "yellow")]
+    Yellow,
+
+    #[serde(rename = "red")]
+    Red,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndexStatus {
+    #[serde(rename = "open")]
+    Open,
+
+    #[serde(rename = "close")]
+    Close,
+}
+
+// the _cat APIs return numbers as strings, and null for closed indices
+pub(crate) fn deserialize_u64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+    where D: Deserializer<'de>
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(value)) => value.parse().map(Some).map_err(serde::de::Error::custom),
+        Some(Value::Number(value)) => value.as_u64().map(Some).ok_or_else(|| serde::de::Error::custom("expected an unsigned integer")),
+        Some(Value::Null) | None => Ok(None),
+        Some(value) => Err(serde::de::Error::custom(format!("expected a number, got {}", value))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{HealthStatus, IndexInfo, IndexStatus};
+
+    #[test]
+    fn test_parse_open_index() {
+        let json = r#"{"health":"yellow","status":"open","index":"library","uuid":"u1","pri":"1","rep":"1","docs.count":"42","docs.deleted":"0","store.size":"2048","pri.store.size":"2048"}"#;
+        let info: IndexInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.health, Some(HealthStatus::Yellow));
+        assert_eq!(info.status, IndexStatus::Open);
+        assert_eq!(info.docs_count, Some(42));
+        assert_eq!(info.store_size, Some(2048));
+    }
+
+    #[test]
+    fn test_parse_closed_index() {
+        let json = r#"{"health":null,"status":"close","index":"library","uuid":"u1","pri":"1","rep":"1","docs.count":null,"docs.deleted":null,"store.size":null,"pri.store.size":null}"#;
+        let info: IndexInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.health, None);
+        assert_eq!(info.status, IndexStatus::Close);
+        assert_eq!(info.docs_count, None);
+    }
 }
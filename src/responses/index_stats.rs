@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Statistics of a single index, combining the `_stats` and `_mapping` APIs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IndexStats {
+    pub index: String,
+
+    pub docs_count: u64,
+
+    pub docs_deleted: u64,
+
+    pub store_size_in_bytes: u64,
+
+    pub segments_count: u64,
+
+    pub segments_memory_in_bytes: u64,
+
+    pub refresh_total: u64,
+
+    pub refresh_time_in_millis: u64,
+
+    pub indexing_total: u64,
+
+    pub indexing_time_in_millis: u64,
+
+    pub fields: Vec<FieldMapping>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldMapping {
+    /// Dotted path of the field, e.g. `sourceName.keyword`.
+    pub name: String,
+
+    pub field_type: String,
+}
+
+impl IndexStats {
+    pub fn new(index: String, stats: &StatsTotal, mapping: &Value) -> Self {
+        let mut fields = vec![];
+        flatten_mapping_properties("", &mapping["mappings"]["properties"], &mut fields);
+        IndexStats {
+            index,
+            docs_count: stats.docs.count,
+            docs_deleted: stats.docs.deleted,
+            store_size_in_bytes: stats.store.size_in_bytes,
+            segments_count: stats.segments.count,
+            segments_memory_in_bytes: stats.segments.memory_in_bytes,
+            refresh_total: stats.refresh.total,
+            refresh_time_in_millis: stats.refresh.total_time_in_millis,
+            indexing_total: stats.indexing.index_total,
+            indexing_time_in_millis: stats.indexing.index_time_in_millis,
+            fields,
+        }
+    }
+}
+
+// walk the "properties" of a mapping, including object properties and multi-fields
+pub fn flatten_mapping_properties(prefix: &str, properties: &Value, fields: &mut Vec<FieldMapping>) {
+    let properties = match properties.as_object() {
+        Some(properties) => properties,
+        None => return,
+    };
+    for (name, property) in properties {
+        let name = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+        let field_type = property["type"].as_str().unwrap_or("object").to_string();
+        fields.push(FieldMapping { name: name.clone(), field_type });
+        flatten_mapping_properties(&name, &property["properties"], fields);
+        flatten_mapping_properties(&name, &property["fields"], fields);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndicesStatsResponse {
+    #[serde(rename = "indices")]
+    pub indices: HashMap<String, IndexStatsEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexStatsEntry {
+    #[serde(rename = "total")]
+    pub total: StatsTotal,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StatsTotal {
+    #[serde(rename = "docs", default)]
+    pub docs: DocsStats,
+
+    #[serde(rename = "store", default)]
+    pub store: StoreStats,
+
+    #[serde(rename = "segments", default)]
+    pub segments: SegmentsStats,
+
+    #[serde(rename = "refresh", default)]
+    pub refresh: RefreshStats,
+
+    #[serde(rename = "indexing", default)]
+    pub indexing: IndexingStats,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DocsStats {
+    #[serde(rename = "count", default)]
+    pub count: u64,
+
+    #[serde(rename = "deleted", default)]
+    pub deleted: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StoreStats {
+    #[serde(rename = "size_in_bytes", default)]
+    pub size_in_bytes: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SegmentsStats {
+    #[serde(rename = "count", default)]
+    pub count: u64,
+
+    #[serde(rename = "memory_in_bytes", default)]
+    pub memory_in_bytes: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RefreshStats {
+    #[serde(rename = "total", default)]
+    pub total: u64,
+
+    #[serde(rename = "total_time_in_millis", default)]
+    pub total_time_in_millis: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IndexingStats {
+    #[serde(rename = "index_total", default)]
+    pub index_total: u64,
+
+    #[serde(rename = "index_time_in_millis", default)]
+    pub index_time_in_millis: u64,
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+    use super::{flatten_mapping_properties, FieldMapping};
+
+    #[test]
+    fn test_flatten_mapping_properties() {
+        let properties = json!({
+            "text": { "type": "text", "fields": { "keyword": { "type": "keyword" } } },
+            "location": { "properties": { "page": { "type": "long" } } }
+        });
+        let mut fields = vec![];
+        flatten_mapping_properties("", &properties, &mut fields);
+        assert!(fields.contains(&FieldMapping { name: "text".to_string(), field_type: "text".to_string() }));
+        assert!(fields.contains(&FieldMapping { name: "text.keyword".to_string(), field_type: "keyword".to_string() }));
+        assert!(fields.contains(&FieldMapping { name: "location".to_string(), field_type: "object".to_string() }));
+        assert!(fields.contains(&FieldMapping { name: "location.page".to_string(), field_type: "long".to_string() }));
+    }
+}
@@ -1,3 +1,19 @@
 pub mod index_info;
 pub mod search_result;
-pub mod check_if_exist;
\ No newline at end of file
+pub mod check_if_exist;
+pub mod task;
+pub mod delete_by_query;
+pub mod update_by_query;
+pub mod removal_report;
+pub mod alias_info;
+pub mod index_stats;
+pub mod cluster_health;
+pub mod error_response;
+pub mod suggest_result;
+pub mod facets;
+pub mod highlight_offsets;
+pub mod count_result;
+pub mod hybrid_result;
+pub mod analyze_result;
+pub mod bulk_result;
+pub mod distant_response;
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a destructive index operation: the indices actually deleted and
+/// the reason each of the others was refused or failed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RemovalReport {
+    pub removed: Vec<String>,
+
+    pub failed: Vec<RemovalFailure>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemovalFailure {
+    pub index: String,
+
+    pub reason: String,
+}
@@ -11,10 +11,14 @@
 //     let model: [object Object] = serde_json::from_str(&json).unwrap();
 // }
 
+use std::collections::HashMap;
 use carrel_commons::carrel::shared::search::v1::{CarrelSearchResponse, CarrelSearchResult, CarrelSearchResultHighlight, CarrelSearchResultItem, CarrelSearchResultMetadata};
 use carrel_commons::generic::api::query::v1::SearchResultMetadata;
 use serde::{Deserialize, Serialize};
 use regex::Regex;
+use crate::responses::distant_response::DistantSearchResponse;
+use crate::responses::facets::{BucketAggregation, Facet};
+use crate::responses::highlight_offsets::{locate_field_highlights, HighlightSpan};
 
 use lazy_static::lazy_static;
 
@@ -27,6 +31,20 @@ pub fn extract_em_content(input: &str) -> Vec<String> {
         .map(|match_| match_.as_str().to_string())
         .collect()
 }
+
+// the content between the highlight tags the search was requested with
+pub fn extract_tagged_content(input: &str, tags: &HighlightTags) -> Vec<String> {
+    if tags.pre_tag == "<em>" && tags.post_tag == "</em>" {
+        return extract_em_content(input);
+    }
+    let tag_regex = Regex::new(&format!("{}(.*?){}", regex::escape(&tags.pre_tag), regex::escape(&tags.post_tag)))
+        .expect("escaped highlight tags are a valid regex");
+    tag_regex.captures_iter(input)
+        .filter_map(|cap| cap.get(1))
+        .map(|match_| match_.as_str().to_string())
+        .collect()
+}
+
 impl From<DistantElasticSearchResult> for CarrelSearchResponse {
     fn from(result: DistantElasticSearchResult) -> Self {
         let mut carrel_search_results: Vec<CarrelSearchResult> = vec![];
@@ -39,16 +57,17 @@ impl From<DistantElasticSearchResult> for CarrelSearchResponse {
             let source = hit.source.clone();
             let carrel_search_result_item = source;
             let highlights: Vec<CarrelSearchResultHighlight> = hit.highlight.as_ref() // Convert to reference
-                .map(|h| h.text.clone().unwrap_or_else(Vec::new)) // Work with reference
+                .map(|h| h.fields()) // Work with reference
                 .unwrap_or_else(Vec::new) // Provide default for None
-                .iter()
-                .map(|text| CarrelSearchResultHighlight {
-                    field: "text".to_string(),
+                .into_iter()
+                .flat_map(|(field, fragments)| fragments.iter().map(move |text| CarrelSearchResultHighlight {
+                    field: field.clone(),
                     text: text.clone(),
-                }).collect();
+                }))
+                .collect();
             let highlights_extracted: Vec<String> = highlights
                 .iter()
-                .flat_map(|highlight| extract_em_content(&highlight.text))
+                .flat_map(|highlight| extract_tagged_content(&highlight.text, &result.highlight_tags))
                 .collect();
             let metadata: CarrelSearchResultMetadata = CarrelSearchResultMetadata {
                 index: index as i32,
@@ -88,6 +107,77 @@ pub struct DistantElasticSearchResult {
 
     #[serde(rename = "took")]
     pub took: i64,
+
+    #[serde(rename = "aggregations", default)]
+    pub aggregations: HashMap<String, BucketAggregation>,
+
+    /// The tags the highlights were requested with, set by the client after the search.
+    #[serde(skip)]
+    pub highlight_tags: HighlightTags,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighlightTags {
+    pub pre_tag: String,
+
+    pub post_tag: String,
+}
+
+impl Default for HighlightTags {
+    fn default() -> Self {
+        HighlightTags {
+            pre_tag: "<em>".to_string(),
+            post_tag: "</em>".to_string(),
+        }
+    }
+}
+
+/// Private use characters that never occur in indexed text, so the highlighted
+/// spans can be located even when the text contains "<em>".
+pub const OFFSET_MARKER_PRE_TAG: &str = "\u{E000}";
+pub const OFFSET_MARKER_POST_TAG: &str = "\u{E001}";
+
+impl HighlightTags {
+    pub fn offset_markers() -> Self {
+        HighlightTags {
+            pre_tag: OFFSET_MARKER_PRE_TAG.to_string(),
+            post_tag: OFFSET_MARKER_POST_TAG.to_string(),
+        }
+    }
+}
+
+impl DistantElasticSearchResult {
+    // the requested aggregations as facets, sorted by name
+    pub fn facets(&self) -> Vec<Facet> {
+        let mut facets: Vec<Facet> = self.aggregations.iter()
+            .map(|(name, aggregation)| Facet::new(name.clone(), aggregation))
+            .collect();
+        facets.sort_by(|a, b| a.name.cmp(&b.name));
+        facets
+    }
+
+    // the library (index name local to the namespace) each hit came from, in the order of the hits
+    pub fn hit_libraries(&self) -> Vec<&str> {
+        self.hits.hits.iter().map(|hit| hit.index.as_str()).collect()
+    }
+
+    // highlighted spans of each hit located in its source fields, in the order of the hits
+    pub fn highlight_spans(&self) -> Vec<Vec<HighlightSpan>> {
+        self.hits.hits.iter()
+            .map(|hit| hit.highlight_spans(&self.highlight_tags))
+            .collect()
+    }
+
+    pub fn into_distant_response(self) -> DistantSearchResponse {
+        let libraries = self.hit_libraries().into_iter().map(str::to_string).collect();
+        let facets = self.facets();
+        DistantSearchResponse {
+            response: self.into(),
+            libraries,
+            facets,
+            component_scores: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -123,25 +213,95 @@ pub struct Hit {
     pub highlight: Option<Highlight>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Hit {
+    // the value of a source field by its CarrelSearchResultItem field name
+    pub fn source_field(&self, field: &str) -> Option<&str> {
+        match field {
+            "text" => Some(&self.source.text),
+            "title" => Some(&self.source.title),
+            "context" => Some(&self.source.context),
+            "source_name" => self.source.source_name.as_deref(),
+            "file_path" => self.source.file_path.as_deref(),
+            "unique_id" => Some(&self.source.unique_id),
+            _ => None,
+        }
+    }
+
+    pub fn highlight_spans(&self, tags: &HighlightTags) -> Vec<HighlightSpan> {
+        let highlight = match &self.highlight {
+            Some(highlight) => highlight,
+            None => return vec![],
+        };
+        highlight.fields()
+            .into_iter()
+            .flat_map(|(field, fragments)| {
+                let value = self.source_field(&field).unwrap_or_default();
+                locate_field_highlights(&field, value, fragments, tags)
+            })
+            .collect()
+    }
+}
+
+/// Highlighted fragments keyed by the indexed field name, for every field Elasticsearch highlighted.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Highlight {
-    #[serde(rename = "text")]
-    pub text: Option<Vec<String>>,
+    pub fields: HashMap<String, Vec<String>>,
+}
 
-    #[serde(rename = "title")]
-    pub title: Option<Vec<String>>,
+// indexed names of the CarrelSearchResultItem fields and their field names, in display order
+const ITEM_FIELDS: [(&str, &str); 6] = [
+    ("text", "text"),
+    ("title", "title"),
+    ("context", "context"),
+    ("sourceName", "source_name"),
+    ("filePath", "file_path"),
+    ("uniqueId", "unique_id"),
+];
+
+impl Highlight {
+    pub fn get(&self, indexed_field: &str) -> Option<&Vec<String>> {
+        self.fields.get(indexed_field)
+    }
+
+    pub fn text(&self) -> Option<&Vec<String>> {
+        self.get("text")
+    }
+
+    pub fn title(&self) -> Option<&Vec<String>> {
+        self.get("title")
+    }
 
-    #[serde(rename = "filePath")]
-    pub context: Option<Vec<String>>,
+    pub fn context(&self) -> Option<&Vec<String>> {
+        self.get("context")
+    }
 
-    #[serde(rename = "sourceName")]
-    pub source_name: Option<Vec<String>>,
+    pub fn source_name(&self) -> Option<&Vec<String>> {
+        self.get("sourceName")
+    }
 
-    #[serde(rename = "filePath")]
-    pub file_path: Option<Vec<String>>,
+    pub fn file_path(&self) -> Option<&Vec<String>> {
+        self.get("filePath")
+    }
 
-    #[serde(rename = "uniqueId")]
-    pub unique_id: Option<Vec<String>>,
+    pub fn unique_id(&self) -> Option<&Vec<String>> {
+        self.get("uniqueId")
+    }
+
+    // fragments of each highlighted field, labelled with the CarrelSearchResultItem field name;
+    // the item fields come first, other indexed fields follow sorted by name
+    pub fn fields(&self) -> Vec<(String, &Vec<String>)> {
+        let mut fields: Vec<(String, &Vec<String>)> = ITEM_FIELDS.iter()
+            .filter_map(|(indexed_field, field)| self.get(indexed_field).map(|fragments| (field.to_string(), fragments)))
+            .collect();
+        let mut other_fields: Vec<(String, &Vec<String>)> = self.fields.iter()
+            .filter(|(indexed_field, _)| !ITEM_FIELDS.iter().any(|(item_field, _)| item_field == indexed_field))
+            .map(|(indexed_field, fragments)| (indexed_field.clone(), fragments))
+            .collect();
+        other_fields.sort_by(|a, b| a.0.cmp(&b.0));
+        fields.extend(other_fields);
+        fields
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -176,6 +336,55 @@ pub enum Type {
 
 #[cfg(test)]
 mod test {
+    #[test]
+    fn test_facets() {
+        let json = r#"{"took":3,"timed_out":false,"hits":{"total":{"value":2,"relation":"eq"},"max_score":null,"hits":[]},
+            "aggregations":{"tags":{"doc_count_error_upper_bound":0,"sum_other_doc_count":1,"buckets":[{"key":"ai","doc_count":2}]},
+            "modified":{"buckets":[{"key_as_string":"2023-01-01T00:00:00.000Z","key":1672531200000,"doc_count":2}]}}}"#;
+        let result: super::DistantElasticSearchResult = serde_json::from_str(json).unwrap();
+        let facets = result.facets();
+        assert_eq!(facets.len(), 2);
+        assert_eq!(facets[0].name, "modified");
+        assert_eq!(facets[0].buckets[0].label, "2023-01-01T00:00:00.000Z");
+        assert_eq!(facets[1].buckets[0].label, "ai");
+        assert_eq!(facets[1].buckets[0].count, 2);
+        assert_eq!(facets[1].other_count, 1);
+        let response = result.into_distant_response();
+        assert_eq!(response.facets, facets);
+        assert!(response.libraries.is_empty());
+        assert!(response.component_scores.is_none());
+    }
+
+    #[test]
+    fn test_extract_tagged_content() {
+        let tags = super::HighlightTags { pre_tag: "<mark>".to_string(), post_tag: "</mark>".to_string() };
+        let output = super::extract_tagged_content("<mark>hello</mark> <em>not</em> <mark>world</mark>", &tags);
+        assert_eq!(output, vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn test_highlights_for_each_field() {
+        let json = r#"{"took":3,"timed_out":false,"hits":{"total":{"value":1,"relation":"eq"},"max_score":1.0,"hits":[
+            {"_id":"a","_index":"library","_score":1.0,"_type":"pdf","_source":{"uniqueId":"a","text":"apple pie","title":"Apple"},
+             "highlight":{"text":["<em>apple</em> pie"],"title":["<em>Apple</em>"]}}]}}"#;
+        let result: super::DistantElasticSearchResult = serde_json::from_str(json).unwrap();
+        let response: super::CarrelSearchResponse = result.into();
+        let metadata = response.results[0].metadata.as_ref().unwrap();
+        let fields: Vec<&str> = metadata.highlights.iter().map(|highlight| highlight.field.as_str()).collect();
+        assert_eq!(fields, vec!["text", "title"]);
+        assert_eq!(metadata.highlights_extracted, vec!["apple", "Apple"]);
+    }
+
+    #[test]
+    fn test_highlight_fields() {
+        let json = r#"{"context":["<em>a</em>"],"filePath":["<em>b</em>"],"tags":["<em>c</em>"],"text":["<em>d</em>"]}"#;
+        let highlight: super::Highlight = serde_json::from_str(json).unwrap();
+        assert_eq!(highlight.context().unwrap(), &vec!["<em>a</em>".to_string()]);
+        assert_eq!(highlight.file_path().unwrap(), &vec!["<em>b</em>".to_string()]);
+        let fields: Vec<String> = highlight.fields().into_iter().map(|(field, _)| field).collect();
+        assert_eq!(fields, vec!["text", "context", "file_path", "tags"]);
+    }
+
     #[test]
     fn test_extract_em_content() {
         let input = "<em>hello</em> <em>world</em>";
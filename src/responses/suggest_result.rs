@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// Response of a search request that only contains a `suggest` section.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuggestResult {
+    #[serde(rename = "suggest", default)]
+    pub suggest: HashMap<String, Vec<SuggestEntry>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuggestEntry {
+    #[serde(rename = "text")]
+    pub text: String,
+
+    #[serde(rename = "offset")]
+    pub offset: i64,
+
+    #[serde(rename = "length")]
+    pub length: i64,
+
+    #[serde(rename = "options")]
+    pub options: Vec<SuggestOption>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuggestOption {
+    #[serde(rename = "text")]
+    pub text: String,
+
+    #[serde(rename = "_index")]
+    pub index: Option<String>,
+
+    #[serde(rename = "_id")]
+    pub id: Option<String>,
+
+    #[serde(rename = "_score")]
+    pub score: f64,
+}
+
+impl SuggestResult {
+    // suggestion strings of the named suggester, best first, without duplicates
+    pub fn ranked_texts(&self, name: &str) -> Vec<String> {
+        let mut options: Vec<&SuggestOption> = self.suggest
+            .get(name)
+            .map(|entries| entries.iter().flat_map(|entry| entry.options.iter()).collect())
+            .unwrap_or_default();
+        options.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        let mut texts: Vec<String> = vec![];
+        for option in options {
+            if !texts.contains(&option.text) {
+                texts.push(option.text.clone());
+            }
+        }
+        texts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SuggestResult;
+
+    #[test]
+    fn test_ranked_texts() {
+        let json = r#"{"took":2,"timed_out":false,"suggest":{"carrel_suggest":[{"text":"art","offset":0,"length":3,"options":[
+            {"text":"Artificial intelligence","_index":"library","_id":"a","_score":1.0},
+            {"text":"Art history","_index":"library","_id":"b","_score":3.0},
+            {"text":"Artificial intelligence","_index":"library","_id":"c","_score":1.0}]}]}}"#;
+        let result: SuggestResult = serde_json::from_str(json).unwrap();
+        assert_eq!(result.ranked_texts("carrel_suggest"), vec!["Art history", "Artificial intelligence"]);
+        assert!(result.ranked_texts("missing").is_empty());
+    }
+}
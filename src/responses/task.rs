@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Response of the `_tasks/<task_id>` API, used to poll long running operations
+/// such as delete by query that were started with `wait_for_completion=false`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskStatus {
+    #[serde(rename = "completed")]
+    pub completed: bool,
+
+    #[serde(rename = "task")]
+    pub task: Option<Value>,
+
+    #[serde(rename = "response")]
+    pub response: Option<Value>,
+
+    #[serde(rename = "error")]
+    pub error: Option<Value>,
+}
+
+/// Response returned when an operation is submitted with `wait_for_completion=false`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskSubmitted {
+    #[serde(rename = "task")]
+    pub task: String,
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Summary of a finished update by query task.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateByQueryReport {
+    #[serde(rename = "took", default)]
+    pub took: i64,
+
+    #[serde(rename = "timed_out", default)]
+    pub timed_out: bool,
+
+    #[serde(rename = "total", default)]
+    pub total: i64,
+
+    #[serde(rename = "updated", default)]
+    pub updated: i64,
+
+    #[serde(rename = "batches", default)]
+    pub batches: i64,
+
+    #[serde(rename = "version_conflicts", default)]
+    pub version_conflicts: i64,
+
+    #[serde(rename = "noops", default)]
+    pub noops: i64,
+
+    #[serde(rename = "failures", default)]
+    pub failures: Vec<Value>,
+}
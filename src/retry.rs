@@ -0,0 +1,138 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Status codes retried by default: rate limiting and an unavailable or overloaded cluster.
+pub const DEFAULT_RETRYABLE_STATUSES: [u16; 4] = [429, 502, 503, 504];
+
+/// Retry policy applied by DistantClient to transient failures.
+///
+/// Operations that are not idempotent (scroll, scripted updates, update by query)
+/// are only retried when `retry_non_idempotent` is set.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one. 1 disables retries.
+    pub max_attempts: u32,
+
+    pub initial_backoff: Duration,
+
+    pub max_backoff: Duration,
+
+    /// Pick a random delay between zero and the exponential backoff ("full jitter").
+    pub jitter: bool,
+
+    pub retryable_statuses: Vec<u16>,
+
+    pub retry_non_idempotent: bool,
+
+    /// Called before sleeping for each retry.
+    pub on_retry: Option<Arc<dyn Fn(&RetryEvent) + Send + Sync>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryEvent {
+    pub operation: String,
+
+    /// The attempt that failed, starting at 1.
+    pub attempt: u32,
+
+    pub delay: Duration,
+
+    pub reason: String,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retryable_statuses: DEFAULT_RETRYABLE_STATUSES.to_vec(),
+            retry_non_idempotent: false,
+            on_retry: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn no_retry() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_on_retry(mut self, on_retry: impl Fn(&RetryEvent) + Send + Sync + 'static) -> Self {
+        self.on_retry = Some(Arc::new(on_retry));
+        self
+    }
+
+    pub fn is_retryable_status(&self, status: u16) -> bool {
+        self.retryable_statuses.contains(&status)
+    }
+
+    // whether another attempt may follow the failed attempt
+    pub fn should_retry(&self, attempt: u32, idempotent: bool) -> bool {
+        attempt < self.max_attempts && (idempotent || self.retry_non_idempotent)
+    }
+
+    // exponential backoff for the failed attempt, starting at 1, capped at max_backoff
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let backoff = self.initial_backoff.saturating_mul(1 << exponent).min(self.max_backoff);
+        if self.jitter {
+            backoff.mul_f64(random_fraction())
+        } else {
+            backoff
+        }
+    }
+}
+
+// random number in [0, 1) without pulling in a random number generator crate
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use super::RetryPolicy;
+
+    #[test]
+    fn test_backoff_without_jitter() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            jitter: false,
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_backoff_with_jitter_stays_below_cap() {
+        let policy = RetryPolicy::default();
+        for attempt in 1..10 {
+            assert!(policy.backoff(attempt) <= policy.max_backoff);
+        }
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry(1, true));
+        assert!(!policy.should_retry(3, true));
+        assert!(!policy.should_retry(1, false));
+        assert!(!RetryPolicy::no_retry().should_retry(1, true));
+        assert!(policy.is_retryable_status(429));
+        assert!(!policy.is_retryable_status(400));
+    }
+}